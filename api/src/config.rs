@@ -0,0 +1,86 @@
+use chrono::TimeDelta;
+
+pub(crate) enum OrmLevel {
+    None,
+    Basic,
+    Intermediate,
+}
+
+pub(crate) struct DriverRules {
+    pub(crate) require_senior: bool,
+    pub(crate) required_orm: OrmLevel,
+    pub(crate) require_cap_license: bool,
+}
+
+pub(crate) struct InspectionRules {
+    pub(crate) valid_for: TimeDelta,
+    pub(crate) pressure_tolerance: i32,
+}
+
+pub(crate) struct ScheduleRules {
+    pub(crate) approval_stages: Vec<String>,
+}
+
+pub(crate) struct Config {
+    pub(crate) driver: DriverRules,
+    pub(crate) inspection: InspectionRules,
+    pub(crate) schedule: ScheduleRules,
+}
+
+impl Config {
+    /// Reads optional settings from the environment. Anything unset falls back
+    /// to the wing's standing requirements.
+    pub(crate) fn from_env() -> Self {
+        Config {
+            driver: DriverRules {
+                require_senior: flag("DRIVER_REQUIRE_SENIOR", true),
+                required_orm: match dotenvy::var("DRIVER_REQUIRED_ORM").as_deref() {
+                    Ok("none") => OrmLevel::None,
+                    Ok("intermediate") => OrmLevel::Intermediate,
+                    Ok("basic") | Err(_) => OrmLevel::Basic,
+                    Ok(other) => panic!("Invalid DRIVER_REQUIRED_ORM \"{other}\""),
+                },
+                require_cap_license: flag("DRIVER_REQUIRE_CAP_LICENSE", false),
+            },
+            inspection: InspectionRules {
+                valid_for: TimeDelta::hours(number("INSPECTION_VALID_HOURS", 24)),
+                pressure_tolerance: number("INSPECTION_PRESSURE_TOLERANCE", 3) as i32,
+            },
+            schedule: ScheduleRules {
+                approval_stages: list(
+                    "SCHEDULE_APPROVAL_STAGES",
+                    &["training_officer", "deputy_commander", "commandant"],
+                ),
+            },
+        }
+    }
+}
+
+fn flag(name: &str, default: bool) -> bool {
+    match dotenvy::var(name).as_deref() {
+        Ok("true") => true,
+        Ok("false") => false,
+        Err(_) => default,
+        Ok(other) => panic!("Invalid {name} \"{other}\""),
+    }
+}
+
+fn number(name: &str, default: i64) -> i64 {
+    match dotenvy::var(name) {
+        Ok(value) => value
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid {name} \"{value}\"")),
+        Err(_) => default,
+    }
+}
+
+fn list(name: &str, default: &[&str]) -> Vec<String> {
+    match dotenvy::var(name) {
+        Ok(value) => value
+            .split(',')
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        Err(_) => default.iter().map(|s| s.to_string()).collect(),
+    }
+}
@@ -0,0 +1,35 @@
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+
+pub(crate) fn csv(
+    file_name: &str,
+    headers: &[&str],
+    rows: &[Vec<String>],
+) -> Result<Response, StatusCode> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+
+    writer
+        .write_record(headers)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    for row in rows {
+        writer
+            .write_record(row)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{file_name}\""),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
+}
@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use chrono::Local;
+use entity::prelude::{
+    Activity, ActivityFlight, ActivityStaff, Attendee, Schedule, ScheduleDay, ScheduleReview,
+    TimeBlock,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, DatabaseTransaction, DbErr, EntityTrait,
+    IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, TransactionTrait,
+};
+use serde::Deserialize;
+
+use crate::AppState;
+use crate::handlers::attendees::has_role;
+use crate::handlers::conflicts::find_conflicts;
+use crate::handlers::headcount::sync_headcounts;
+use crate::handlers::schedules::{APPROVED, CHANGES_REQUESTED, DRAFT, IN_REVIEW, REJECTED};
+use crate::handlers::versions::notify_changes;
+
+const APPROVE: &str = "approve";
+const REJECT: &str = "reject";
+const REQUEST_CHANGES: &str = "request_changes";
+
+#[derive(Deserialize)]
+pub(crate) struct ReviewParameters {
+    capid: i32,
+    action: String,
+    comments: Option<String>,
+}
+
+/// The staff roles that sign off on a schedule, in order.
+pub(crate) async fn get_approval_stages(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    Json(state.config.schedule.approval_stages.clone())
+}
+
+/// Stamps a newly approved version as published, then brings its headcounts
+/// up to date and lets everyone affected know what moved.
+async fn publish(
+    txn: &DatabaseTransaction,
+    schedule: entity::schedule::Model,
+) -> Result<(), DbErr> {
+    let mut model = schedule.into_active_model();
+    model.published_at = Set(Some(Local::now().naive_local()));
+    let schedule = model.update(txn).await?;
+
+    sync_headcounts(txn, &schedule).await?;
+    notify_changes(txn, &schedule).await
+}
+
+/// Sends a draft for approval, unless it has conflicts that are errors, in
+/// which case they come back with a 422.
+pub(crate) async fn submit_schedule(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Response {
+    let schedule = match Schedule::find_by_id(id).one(&state.db).await {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+
+    if schedule.status != DRAFT && schedule.status != CHANGES_REQUESTED {
+        return StatusCode::CONFLICT.into_response();
+    }
+
+    match find_conflicts(&state.db, id).await {
+        Ok(conflicts) if conflicts.iter().any(|c| c.is_error()) => {
+            return (StatusCode::UNPROCESSABLE_ENTITY, Json(conflicts)).into_response();
+        }
+        Ok(_) => {}
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+
+    // With no stages configured there's nobody to route to
+    let status = if state.config.schedule.approval_stages.is_empty() {
+        APPROVED
+    } else {
+        IN_REVIEW
+    };
+
+    let txn = match state.db.begin().await {
+        Ok(txn) => txn,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+
+    let mut model = schedule.into_active_model();
+    model.status = Set(status.to_string());
+    model.stage = Set(0);
+
+    let schedule = match model.update(&txn).await {
+        Ok(schedule) => schedule,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+
+    if status == APPROVED && publish(&txn, schedule).await.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    match txn.commit().await {
+        Ok(_) => StatusCode::OK.into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+/// Records a decision at the schedule's current stage. Approving moves it to
+/// the next stage, or approves it outright at the last one; rejecting or
+/// requesting changes needs comments.
+pub(crate) async fn review_schedule(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<ReviewParameters>,
+) -> impl IntoResponse {
+    let comments = data
+        .comments
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+
+    match data.action.as_str() {
+        APPROVE => {}
+        REJECT | REQUEST_CHANGES if comments.is_some() => {}
+        _ => return StatusCode::BAD_REQUEST,
+    }
+
+    let schedule = match Schedule::find_by_id(id).one(&state.db).await {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    if schedule.status != IN_REVIEW {
+        return StatusCode::CONFLICT;
+    }
+
+    let stages = &state.config.schedule.approval_stages;
+    let Some(role) = stages.get(schedule.stage as usize) else {
+        return StatusCode::CONFLICT;
+    };
+
+    match has_role(&state.db, data.capid, &[role]).await {
+        Ok(true) => {}
+        Ok(false) => return StatusCode::FORBIDDEN,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    let txn = match state.db.begin().await {
+        Ok(txn) => txn,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    let review = entity::schedule_review::ActiveModel {
+        id: NotSet,
+        schedule_id: Set(id),
+        stage: Set(schedule.stage),
+        role: Set(role.clone()),
+        capid: Set(data.capid),
+        action: Set(data.action.clone()),
+        comments: Set(comments),
+        acted_at: Set(Local::now().naive_local()),
+    };
+
+    if review.insert(&txn).await.is_err() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let next = schedule.stage + 1;
+    let mut model = schedule.into_active_model();
+    match data.action.as_str() {
+        APPROVE if next as usize >= stages.len() => model.status = Set(APPROVED.to_string()),
+        APPROVE => model.stage = Set(next),
+        REJECT => model.status = Set(REJECTED.to_string()),
+        _ => model.status = Set(CHANGES_REQUESTED.to_string()),
+    }
+
+    let schedule = match model.update(&txn).await {
+        Ok(schedule) => schedule,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    if schedule.status == APPROVED && publish(&txn, schedule).await.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    match txn.commit().await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn get_schedule_reviews(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<entity::schedule_review::ModelEx>>, StatusCode> {
+    match ScheduleReview::load()
+        .filter(entity::schedule_review::Column::ScheduleId.eq(id))
+        .with(Attendee)
+        .order_by_asc(entity::schedule_review::Column::ActedAt)
+        .all(&state.db)
+        .await
+    {
+        Ok(reviews) => Ok(Json(reviews)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Copies a schedule's days, blocks and activities into a new draft version.
+/// Activities keep their `uid` so they can be followed across versions.
+async fn clone_schedule(
+    txn: &DatabaseTransaction,
+    schedule: &entity::schedule::Model,
+) -> Result<entity::schedule::Model, DbErr> {
+    let draft = entity::schedule::ActiveModel {
+        id: NotSet,
+        name: Set(schedule.name.clone()),
+        created_at: Set(Local::now().naive_local()),
+        series_id: Set(schedule.series_id),
+        version: Set(schedule.version + 1),
+        status: Set(DRAFT.to_string()),
+        stage: Set(0),
+        previous_version_id: Set(Some(schedule.id)),
+        published_at: Set(None),
+    }
+    .insert(txn)
+    .await?;
+
+    for day in ScheduleDay::find()
+        .filter(entity::schedule_day::Column::ScheduleId.eq(schedule.id))
+        .all(txn)
+        .await?
+    {
+        let new_day = entity::schedule_day::ActiveModel {
+            id: NotSet,
+            schedule_id: Set(draft.id),
+            date: Set(day.date),
+            uniform: Set(day.uniform),
+        }
+        .insert(txn)
+        .await?;
+
+        for block in TimeBlock::find()
+            .filter(entity::time_block::Column::DayId.eq(day.id))
+            .all(txn)
+            .await?
+        {
+            let new_block = entity::time_block::ActiveModel {
+                id: NotSet,
+                day_id: Set(new_day.id),
+                name: Set(block.name),
+                starts_at: Set(block.starts_at),
+                ends_at: Set(block.ends_at),
+            }
+            .insert(txn)
+            .await?;
+
+            let activities = Activity::find()
+                .filter(entity::activity::Column::BlockId.eq(block.id))
+                .all(txn)
+                .await?;
+
+            let mut ids = HashMap::new();
+            for activity in activities {
+                let new_activity = entity::activity::ActiveModel {
+                    id: NotSet,
+                    block_id: Set(new_block.id),
+                    uid: Set(activity.uid),
+                    name: Set(activity.name),
+                    description: Set(activity.description),
+                    location_id: Set(activity.location_id),
+                    headcount: Set(activity.headcount),
+                }
+                .insert(txn)
+                .await?;
+                ids.insert(activity.id, new_activity.id);
+            }
+
+            let flights = ActivityFlight::find()
+                .filter(entity::activity_flight::Column::ActivityId.is_in(ids.keys().copied()))
+                .all(txn)
+                .await?;
+            if !flights.is_empty() {
+                ActivityFlight::insert_many(flights.into_iter().map(|f| {
+                    entity::activity_flight::ActiveModel {
+                        id: NotSet,
+                        activity_id: Set(ids[&f.activity_id]),
+                        flight_id: Set(f.flight_id),
+                    }
+                }))
+                .exec(txn)
+                .await?;
+            }
+
+            let staff = ActivityStaff::find()
+                .filter(entity::activity_staff::Column::ActivityId.is_in(ids.keys().copied()))
+                .all(txn)
+                .await?;
+            if !staff.is_empty() {
+                ActivityStaff::insert_many(staff.into_iter().map(|s| {
+                    entity::activity_staff::ActiveModel {
+                        id: NotSet,
+                        activity_id: Set(ids[&s.activity_id]),
+                        capid: Set(s.capid),
+                    }
+                }))
+                .exec(txn)
+                .await?;
+            }
+        }
+    }
+
+    Ok(draft)
+}
+
+/// Opens a new draft from an approved or rejected version. A series only has
+/// one open draft at a time.
+pub(crate) async fn revise_schedule(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<entity::schedule::Model>, StatusCode> {
+    let schedule = match Schedule::find_by_id(id).one(&state.db).await {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    if schedule.status != APPROVED && schedule.status != REJECTED {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let open = Schedule::find()
+        .filter(entity::schedule::Column::SeriesId.eq(schedule.series_id))
+        .filter(entity::schedule::Column::Status.is_in([DRAFT, IN_REVIEW, CHANGES_REQUESTED]))
+        .count(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if open > 0 {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let latest = Schedule::find()
+        .filter(entity::schedule::Column::SeriesId.eq(schedule.series_id))
+        .order_by_desc(entity::schedule::Column::Version)
+        .one(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map(|s| s.version)
+        .unwrap_or(schedule.version);
+
+    let txn = state
+        .db
+        .begin()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // Numbered after the newest version even when revising an older one
+    let base = entity::schedule::Model {
+        version: latest,
+        ..schedule
+    };
+    let draft = clone_schedule(&txn, &base)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    match txn.commit().await {
+        Ok(_) => Ok(Json(draft)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
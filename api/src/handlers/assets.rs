@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use chrono::NaiveDateTime;
+use entity::prelude::{
+    Attendee, Flight, FlightMember, HandReceipt, Radio, RadioType, Vehicle, VehicleType,
+};
+use sea_orm::{ColumnTrait, DbConn, DbErr, EntityTrait, PaginatorTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+
+use crate::AppState;
+use crate::export;
+use crate::handlers::drivers::Override;
+use crate::handlers::receipts::Custody;
+use crate::handlers::trips::{Arrival, Departure};
+use crate::handlers::{radios, vehicles};
+
+#[derive(Serialize)]
+#[serde(tag = "asset_type", rename_all = "snake_case")]
+pub(crate) enum Asset {
+    Radio(entity::radio::ModelEx),
+    Vehicle(entity::vehicle::ModelEx),
+}
+
+/// Whether a radio or vehicle already carries the tag. Each table only
+/// enforces its own tags, so anything that sets one checks here first.
+pub(crate) async fn tag_in_use(db: &DbConn, tag: &str) -> Result<bool, DbErr> {
+    let radios = Radio::find()
+        .filter(entity::radio::Column::PropertyTagNumber.eq(tag))
+        .count(db)
+        .await?;
+    let vehicles = Vehicle::find()
+        .filter(entity::vehicle::Column::PropertyTagNumber.eq(tag))
+        .count(db)
+        .await?;
+
+    Ok(radios + vehicles > 0)
+}
+
+/// Looks an asset up by tag. A tag on both a radio and a vehicle (from before
+/// tags were checked across both) is a 409 rather than a guess.
+async fn find_by_tag(db: &DbConn, tag: &str) -> Result<Asset, StatusCode> {
+    let radio = Radio::load()
+        .filter(entity::radio::Column::PropertyTagNumber.eq(tag))
+        .with(Attendee)
+        .with(RadioType)
+        .one(db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let vehicle = Vehicle::load()
+        .filter(entity::vehicle::Column::PropertyTagNumber.eq(tag))
+        .with(Attendee)
+        .with(VehicleType)
+        .one(db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    match (radio, vehicle) {
+        (Some(radio), None) => Ok(Asset::Radio(radio)),
+        (None, Some(vehicle)) => Ok(Asset::Vehicle(vehicle)),
+        (Some(_), Some(_)) => Err(StatusCode::CONFLICT),
+        (None, None) => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+pub(crate) async fn get_asset_by_tag(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Asset>, StatusCode> {
+    let tag = match params.get("tag") {
+        Some(tag) => tag,
+        None => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    find_by_tag(&state.db, tag).await.map(Json)
+}
+
+#[derive(Deserialize)]
+pub(crate) struct IssueAssetParameters {
+    tag: String,
+    #[serde(flatten)]
+    custody: Custody,
+    #[serde(flatten)]
+    departure: Option<Departure>,
+    #[serde(flatten)]
+    approval: Option<Override>,
+}
+
+pub(crate) async fn issue_asset(
+    State(state): State<Arc<AppState>>,
+    Json(data): Json<IssueAssetParameters>,
+) -> Result<Response, StatusCode> {
+    match find_by_tag(&state.db, &data.tag).await? {
+        Asset::Radio(radio) => match radios::issue(&state.db, radio.id, data.custody).await {
+            Ok(receipt) => Ok(receipt.into_response()),
+            Err(response) => Ok(response),
+        },
+        Asset::Vehicle(vehicle) => {
+            // Vehicles can't leave without the trip details for the usage log
+            let departure = data.departure.ok_or(StatusCode::BAD_REQUEST)?;
+
+            match vehicles::issue(&state, vehicle.id, data.custody, departure, data.approval).await
+            {
+                Ok(issued) => Ok(issued.into_response()),
+                Err(response) => Ok(response),
+            }
+        }
+    }
+}
+
+pub(crate) async fn return_asset(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let tag = match params.get("tag") {
+        Some(tag) => tag,
+        None => return StatusCode::BAD_REQUEST,
+    };
+
+    let in_service = match params.get("inService") {
+        Some(s) => match s.parse() {
+            Ok(b) => b,
+            Err(_) => return StatusCode::BAD_REQUEST,
+        },
+        None => true,
+    };
+
+    match find_by_tag(&state.db, tag).await {
+        Ok(Asset::Radio(radio)) => {
+            let problem = params.get("problem").cloned();
+            radios::turn_in(&state.db, radio.id, in_service, problem).await
+        }
+        Ok(Asset::Vehicle(vehicle)) => {
+            let arrival = match Arrival::from_params(&params) {
+                Ok(arrival) => arrival,
+                Err(status) => return status,
+            };
+
+            let problem = params.get("problem").cloned();
+            vehicles::turn_in(&state.db, vehicle.id, in_service, problem, arrival).await
+        }
+        Err(status) => status,
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct OutstandingItem {
+    asset_type: &'static str,
+    id: i32,
+    property_tag_number: Option<String>,
+    description: String,
+    issued_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct OutstandingHolder {
+    capid: i32,
+    rank: String,
+    first_name: String,
+    last_name: String,
+    cell_phone: Option<String>,
+    home_phone: Option<String>,
+    email: String,
+    items: Vec<OutstandingItem>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct TurnInGroup {
+    group: String,
+    holders: Vec<OutstandingHolder>,
+}
+
+async fn outstanding(db: &DbConn) -> Result<Vec<TurnInGroup>, DbErr> {
+    let radios = Radio::load()
+        .filter(entity::radio::Column::IssuedTo.is_not_null())
+        .with(RadioType)
+        .all(db)
+        .await?;
+
+    let vehicles = Vehicle::load()
+        .filter(entity::vehicle::Column::IssuedTo.is_not_null())
+        .with(VehicleType)
+        .all(db)
+        .await?;
+
+    let receipts = HandReceipt::find()
+        .filter(entity::hand_receipt::Column::ReturnedAt.is_null())
+        .all(db)
+        .await?;
+
+    let mut radio_issued = HashMap::new();
+    let mut vehicle_issued = HashMap::new();
+    for receipt in receipts {
+        if let Some(id) = receipt.radio_id {
+            radio_issued.insert(id, receipt.issued_at);
+        }
+        if let Some(id) = receipt.vehicle_id {
+            vehicle_issued.insert(id, receipt.issued_at);
+        }
+    }
+
+    let mut items: HashMap<i32, Vec<OutstandingItem>> = HashMap::new();
+
+    for radio in radios {
+        let description = match radio.radio_type.as_ref() {
+            Some(t) => format!("{} {}", t.make, t.model),
+            None => String::new(),
+        };
+
+        items
+            .entry(radio.issued_to.unwrap())
+            .or_default()
+            .push(OutstandingItem {
+                asset_type: "radio",
+                id: radio.id,
+                property_tag_number: Some(radio.property_tag_number),
+                description,
+                issued_at: radio_issued.get(&radio.id).copied(),
+            });
+    }
+
+    for vehicle in vehicles {
+        let description = match vehicle.vehicle_type.as_ref() {
+            Some(t) => format!("{} {} {}", vehicle.year, t.make, t.model),
+            None => vehicle.year.to_string(),
+        };
+
+        items
+            .entry(vehicle.issued_to.unwrap())
+            .or_default()
+            .push(OutstandingItem {
+                asset_type: "vehicle",
+                id: vehicle.id,
+                property_tag_number: vehicle.property_tag_number,
+                description,
+                issued_at: vehicle_issued.get(&vehicle.id).copied(),
+            });
+    }
+
+    let holders = Attendee::find()
+        .filter(entity::attendee::Column::Capid.is_in(items.keys().copied()))
+        .all(db)
+        .await?;
+
+    // Cadets turn in by flight; anyone not on a flight falls back to their unit
+    let flights: HashMap<i32, String> = FlightMember::find()
+        .filter(entity::flight_member::Column::Capid.is_in(items.keys().copied()))
+        .find_also_related(Flight)
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|(member, flight)| flight.map(|f| (member.capid, f.name)))
+        .collect();
+
+    let mut groups: BTreeMap<String, BTreeMap<(String, String, i32), OutstandingHolder>> =
+        BTreeMap::new();
+
+    for holder in holders {
+        let items = items.remove(&holder.capid).unwrap_or_default();
+        let group = flights.get(&holder.capid).cloned().unwrap_or(holder.unit);
+
+        groups.entry(group).or_default().insert(
+            (
+                holder.last_name.clone(),
+                holder.first_name.clone(),
+                holder.capid,
+            ),
+            OutstandingHolder {
+                capid: holder.capid,
+                rank: holder.rank,
+                first_name: holder.first_name,
+                last_name: holder.last_name,
+                cell_phone: holder.cell_phone,
+                home_phone: holder.home_phone,
+                email: holder.email,
+                items,
+            },
+        );
+    }
+
+    Ok(groups
+        .into_iter()
+        .map(|(group, holders)| TurnInGroup {
+            group,
+            holders: holders.into_values().collect(),
+        })
+        .collect())
+}
+
+pub(crate) async fn get_turn_in_report(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<TurnInGroup>>, StatusCode> {
+    match outstanding(&state.db).await {
+        Ok(report) => Ok(Json(report)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn export_turn_in_report(
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let report = outstanding(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut rows = vec![];
+    for group in report {
+        for holder in group.holders {
+            for item in holder.items {
+                rows.push(vec![
+                    group.group.clone(),
+                    holder.capid.to_string(),
+                    format!("{} {} {}", holder.rank, holder.first_name, holder.last_name),
+                    holder.cell_phone.clone().unwrap_or_default(),
+                    holder.home_phone.clone().unwrap_or_default(),
+                    holder.email.clone(),
+                    item.asset_type.to_string(),
+                    item.id.to_string(),
+                    item.property_tag_number.unwrap_or_default(),
+                    item.description,
+                    item.issued_at
+                        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_default(),
+                ]);
+            }
+        }
+    }
+
+    export::csv(
+        "turn-in-report.csv",
+        &[
+            "Flight/Unit",
+            "CAPID",
+            "Name",
+            "Cell Phone",
+            "Home Phone",
+            "Email",
+            "Item",
+            "ID",
+            "Property Tag",
+            "Description",
+            "Issued",
+        ],
+        &rows,
+    )
+}
@@ -1,17 +1,36 @@
-use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::SystemTime;
 
 use axum::extract::{Json, Multipart, Path, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use calamine::{DataType, HeaderRow, Reader, Xlsx, open_workbook};
-use chrono::NaiveDate;
-use entity::prelude::Attendee;
+use calamine::{Data, DataType, HeaderRow, Reader, Xlsx, open_workbook};
+use chrono::{Local, NaiveDate};
+use entity::prelude::{Attendee, Radio, StaffRole, Vehicle};
 use migration::OnConflict;
-use sea_orm::{ActiveModelTrait, ActiveValue::Set, DbConn, DbErr, EntityTrait};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, DbConn, DbErr, EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter,
+    QueryOrder, SqlErr,
+};
+use serde::Deserialize;
 
 use crate::AppState;
+use crate::import::{ImportReport, UploadKind, save_upload};
+
+pub(crate) fn is_senior_member(attendee: &entity::attendee::Model) -> bool {
+    attendee.member_type.eq_ignore_ascii_case("SENIOR")
+}
+
+/// Reduces a gender as typed or imported ("M", "female", ...) to "M" or "F",
+/// going by the first letter.
+pub(crate) fn normalize_gender(gender: &str) -> Option<&'static str> {
+    match gender.trim().chars().next().map(|c| c.to_ascii_uppercase()) {
+        Some('M') => Some("M"),
+        Some('F') => Some("F"),
+        _ => None,
+    }
+}
 
 pub(crate) async fn get_all_attendees(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     let attendees = Attendee::find()
@@ -45,10 +64,136 @@ pub(crate) async fn delete_attendee(
     match model.delete(&state.db).await {
         Ok(_) => StatusCode::OK,
         Err(DbErr::RecordNotFound(_)) => StatusCode::BAD_REQUEST,
+        // Anyone who has signed for equipment or driven stays on the record
+        Err(e) if matches!(e.sql_err(), Some(SqlErr::ForeignKeyConstraintViolation(_))) => {
+            StatusCode::CONFLICT
+        }
         _ => StatusCode::INTERNAL_SERVER_ERROR,
     }
 }
 
+pub(crate) async fn mark_departed(
+    State(state): State<Arc<AppState>>,
+    Path(capid): Path<i32>,
+) -> impl IntoResponse {
+    let mut model = match Attendee::find_by_id(capid).one(&state.db).await {
+        Ok(Some(model)) => model.into_active_model(),
+        Ok(None) => return StatusCode::BAD_REQUEST,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    let radios = Radio::find()
+        .filter(entity::radio::Column::IssuedTo.eq(capid))
+        .count(&state.db)
+        .await;
+    let vehicles = Vehicle::find()
+        .filter(entity::vehicle::Column::IssuedTo.eq(capid))
+        .count(&state.db)
+        .await;
+
+    match (radios, vehicles) {
+        (Ok(0), Ok(0)) => {}
+        (Ok(_), Ok(_)) => return StatusCode::CONFLICT,
+        _ => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    model.departed_at = Set(Some(Local::now().naive_local()));
+
+    match model.update(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn clear_departed(
+    State(state): State<Arc<AppState>>,
+    Path(capid): Path<i32>,
+) -> impl IntoResponse {
+    let mut model = match Attendee::find_by_id(capid).one(&state.db).await {
+        Ok(Some(model)) => model.into_active_model(),
+        Ok(None) => return StatusCode::BAD_REQUEST,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    model.departed_at = Set(None);
+
+    match model.update(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) const INCIDENT_COMMANDER: &str = "incident_commander";
+pub(crate) const COMMANDER: &str = "commander";
+
+pub(crate) async fn has_role(db: &DbConn, capid: i32, roles: &[&str]) -> Result<bool, DbErr> {
+    let count = StaffRole::find()
+        .filter(entity::staff_role::Column::Capid.eq(capid))
+        .filter(entity::staff_role::Column::Role.is_in(roles.iter().copied()))
+        .count(db)
+        .await?;
+
+    Ok(count > 0)
+}
+
+pub(crate) async fn get_all_staff_roles(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let roles = StaffRole::load()
+        .with(Attendee)
+        .order_by_asc(entity::staff_role::Column::Role)
+        .all(&state.db)
+        .await
+        .expect("Could not get staff roles");
+
+    Json(roles)
+}
+
+#[derive(Deserialize)]
+pub(crate) struct StaffRoleParameters {
+    role: String,
+}
+
+pub(crate) async fn add_staff_role(
+    State(state): State<Arc<AppState>>,
+    Path(capid): Path<i32>,
+    Json(data): Json<StaffRoleParameters>,
+) -> impl IntoResponse {
+    let role = data.role.trim().to_lowercase();
+    if role.is_empty() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let model = entity::staff_role::ActiveModel {
+        id: NotSet,
+        capid: Set(capid),
+        role: Set(role),
+    };
+
+    match model.insert(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(e) => match e.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => StatusCode::CONFLICT,
+            Some(SqlErr::ForeignKeyConstraintViolation(_)) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        },
+    }
+}
+
+pub(crate) async fn remove_staff_role(
+    State(state): State<Arc<AppState>>,
+    Path((capid, role)): Path<(i32, String)>,
+) -> impl IntoResponse {
+    match StaffRole::delete_many()
+        .filter(entity::staff_role::Column::Capid.eq(capid))
+        .filter(entity::staff_role::Column::Role.eq(role))
+        .exec(&state.db)
+        .await
+    {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 pub(crate) async fn create_attendee(
     State(state): State<Arc<AppState>>,
     Json(attendee): Json<entity::attendee::Model>,
@@ -62,201 +207,197 @@ pub(crate) async fn create_attendee(
 pub(crate) async fn create_attendee_bulk(
     State(state): State<Arc<AppState>>,
     mut multipart: Multipart,
-) -> impl IntoResponse {
-    let mut file_path = String::new();
-
-    while let Some(field) = multipart.next_field().await.unwrap() {
-        let file_name = field.file_name().unwrap().to_string();
-        let content_type = field.content_type().unwrap().to_string();
-        let data = field.bytes().await.unwrap();
-
-        match content_type.as_str() {
-            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => {
-                let mut path = PathBuf::from(dotenvy::var("UPLOADS_PATH").unwrap());
-                path.push(format!(
-                    "{}_{file_name}",
-                    SystemTime::now()
-                        .duration_since(SystemTime::UNIX_EPOCH)
-                        .unwrap()
-                        .as_millis()
-                ));
-
-                match tokio::fs::write(&path, data).await {
-                    Ok(_) => {}
-                    Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
-                }
-
-                file_path = path.to_string_lossy().to_string();
-            }
-            _ => return StatusCode::BAD_REQUEST,
-        }
+) -> Result<Json<ImportReport>, StatusCode> {
+    let upload = match save_upload(&mut multipart).await {
+        Ok(upload) => upload,
+        Err(status) => return Err(status),
+    };
+
+    if !matches!(upload.kind, UploadKind::Xlsx) {
+        return Err(StatusCode::BAD_REQUEST);
     }
 
-    let mut excel: Xlsx<_> = open_workbook(file_path).unwrap();
+    let mut excel: Xlsx<_> = open_workbook(upload.path).map_err(|_| StatusCode::BAD_REQUEST)?;
     let sheet = excel
         .with_header_row(HeaderRow::FirstNonEmptyRow)
         .worksheet_range("UniversalReport")
-        .unwrap();
-
-    for row in sheet.rows().skip(1) {
-        let attendee = entity::attendee::Model {
-            capid: row[1].get_float().unwrap().round() as i32,
-            rank: row[5].get_string().unwrap().to_string(),
-            last_name: row[6].get_string().unwrap().to_string(),
-            first_name: row[7].get_string().unwrap().to_string(),
-            middle_name: row[8].get_string().map(str::to_string),
-            unit: format!(
-                "{}-{}-{:0>3}",
-                row[9].get_string().unwrap(),
-                row[10].get_string().unwrap(),
-                row[11].get_string().unwrap()
-            ),
-            gender: row[12].get_string().unwrap().to_string(),
-            date_of_birth: {
-                let date = row[13].get_string().unwrap();
-                NaiveDate::parse_from_str(format!("01/{date}").as_str(), "%d/%m/%y").unwrap()
-            },
-            age_at_start: row[15].get_float().unwrap().round() as i32,
-            age_at_end: row[16].get_float().unwrap().round() as i32,
-            height: match row[17].get_float() {
-                Some(0.0) => None,
-                None => None,
-                opt => opt.map(|v| v.round() as i32),
-            },
-            weight: match row[18].get_float() {
-                Some(0.0) => None,
-                None => None,
-                opt => opt.map(|v| v.round() as i32),
-            },
-            shirt_size: match row[19].get_string() {
-                Some("          ") => None,
-                Some("Unavailable") => None,
-                None => None,
-                opt => opt.map(|s| s.to_string()),
-            },
-            member_type: row[20].get_string().unwrap().to_string(),
-            expiration: {
-                let date = row[21].get_string().unwrap();
-                NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()
-            },
-            member_status: row[22].get_string().unwrap().to_string(),
-            home_phone: row[23].get_string().map(str::to_string),
-            cell_phone: row[24].get_string().map(str::to_string),
-            email: row[27].get_string().unwrap().to_string(),
-            address1: row[28].get_string().unwrap().to_string(),
-            address2: row[29].get_string().unwrap().to_string(),
-            city: row[30].get_string().unwrap().to_string(),
-            state: row[31].get_string().unwrap().to_string(),
-            zip_code: row[32].get_string().unwrap().to_string(),
-            registration_status: row[35].get_string().unwrap().to_string(),
-            is_staff: match row[36].get_string().unwrap() {
-                "Yes" => true,
-                _ => false,
-            },
-            registration_id: row[40].get_float().unwrap().round() as i32,
-            comments: row[42].get_string().map(str::to_string),
-            emergency_contact_name: row[25].get_string().map(str::to_string),
-            emergency_contact_number: row[26].get_string().map(str::to_string),
-            cadet_parent_phone_primary: row[53].get_string().map(str::to_string),
-            cadet_parent_phone_secondary: row[54].get_string().map(str::to_string),
-            cadet_parent_email_primary: row[56].get_string().map(str::to_string),
-            cadet_parent_email_secondary: row[57].get_string().map(str::to_string),
-            unit_commander_name: row[59].get_string().unwrap().to_string(),
-            unit_commander_email: row[60].get_string().unwrap().to_string(),
-            wing_commander_name: row[61].get_string().unwrap().to_string(),
-            wing_commander_email: row[62].get_string().unwrap().to_string(),
-            is_pilot: match row[63].get_string().unwrap() {
-                "Yes" => true,
-                _ => false,
-            },
-            dl_expiration: match row[64].get_string() {
-                Some(" ") => None,
-                None => None,
-                opt => opt.map(|date| NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()),
-            },
-            last_encampment: match row[65].get_string() {
-                Some(" ") => None,
-                None => None,
-                opt => opt.map(|date| NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()),
-            },
-            highest_o_ride: match row[66].get_string().unwrap() {
-                "" => None,
-                val => match val.parse() {
-                    Ok(val) => Some(val),
-                    Err(e) => {
-                        panic!("Could not parse o-ride number: {e}");
-                    }
-                },
-            },
-            aircraft_ground_handling: match row[68].get_string().unwrap() {
-                "Not Complete" => None,
-                date => Some(NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()),
-            },
-            wing_runner: match row[69].get_string().unwrap() {
-                "Not Complete" => None,
-                date => Some(NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()),
-            },
-            orm_basic: match row[70].get_string().unwrap() {
-                "Not Complete" => None,
-                date => Some(NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()),
-            },
-            orm_intermediate: match row[71].get_string().unwrap() {
-                "Not Complete" => None,
-                date => Some(NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()),
-            },
-            cppt_expiration: match row[72].get_string().unwrap() {
-                "" => None,
-                date => Some(NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()),
-            },
-            monthly_safety: match row[73].get_string().unwrap() {
-                "Not Complete" => None,
-                date => Some(NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()),
-            },
-            icut: match row[74].get_string().unwrap() {
-                "Not Complete" => None,
-                date => Some(NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()),
-            },
-            is100: match row[75].get_string().unwrap() {
-                "Not Complete" => None,
-                date => Some(NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()),
-            },
-            is700: match row[76].get_string().unwrap() {
-                "Not Complete" => None,
-                date => Some(NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()),
-            },
-            capt116: match row[77].get_string().unwrap() {
-                "Not Complete" => None,
-                date => Some(NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()),
-            },
-            capt117_part1: match row[78].get_string().unwrap() {
-                "Not Complete" => None,
-                date => Some(NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()),
-            },
-            capt117_part2: match row[79].get_string().unwrap() {
-                "Not Complete" => None,
-                date => Some(NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()),
-            },
-            capt117_part3: match row[80].get_string().unwrap() {
-                "Not Complete" => None,
-                date => Some(NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()),
-            },
-            first_aid: match row[81].get_string().unwrap() {
-                "Not Complete" => None,
-                date => Some(NaiveDate::parse_from_str(date, "%d %b %Y ").unwrap()),
-            },
-            invoice_id: row[82].get_float().map(|v| v.round() as i32),
-            prices_id: row[83].get_float().map(|v| v.round() as i32),
-            invoice_status: row[84].get_string().map(str::to_string),
-            registered_by: row[87].get_string().map(str::to_string),
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let mut report = ImportReport::default();
+
+    for (i, row) in sheet.rows().enumerate().skip(1) {
+        let result = match parse_attendee(row) {
+            Ok(attendee) => {
+                let capid = attendee.capid;
+                create(&state.db, attendee)
+                    .await
+                    .map_err(|e| format!("Could not save CAPID {capid}: {e}"))
+            }
+            Err(e) => Err(e),
         };
+        report.record(i + 1, result);
+    }
 
-        match create(&state.db, attendee).await {
-            Ok(_) => {}
-            Err(_) => return StatusCode::BAD_REQUEST,
-        }
+    Ok(Json(report))
+}
+
+const DATE: &str = "%d %b %Y ";
+
+/// Spreadsheet column letter for a zero-based index, for error messages.
+fn column(n: usize) -> String {
+    let mut n = n + 1;
+    let mut letters = String::new();
+    while n > 0 {
+        n -= 1;
+        letters.insert(0, (b'A' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+    letters
+}
+
+fn invalid(n: usize, value: &str) -> String {
+    format!("Invalid value \"{}\" in column {}", value.trim(), column(n))
+}
+
+fn text(row: &[Data], n: usize) -> Option<&str> {
+    row.get(n).and_then(|c| c.get_string())
+}
+
+fn required(row: &[Data], n: usize) -> Result<&str, String> {
+    text(row, n).ok_or_else(|| format!("Missing value in column {}", column(n)))
+}
+
+fn number(row: &[Data], n: usize) -> Result<i32, String> {
+    match row.get(n) {
+        Some(cell) => match cell.get_float() {
+            Some(v) => Ok(v.round() as i32),
+            None => Err(invalid(n, &cell.to_string())),
+        },
+        None => Err(format!("Missing value in column {}", column(n))),
     }
+}
+
+fn date(row: &[Data], n: usize) -> Result<NaiveDate, String> {
+    let value = required(row, n)?;
+    NaiveDate::parse_from_str(value, DATE).map_err(|_| invalid(n, value))
+}
+
+fn completed(row: &[Data], n: usize) -> Result<Option<NaiveDate>, String> {
+    match required(row, n)? {
+        "Not Complete" => Ok(None),
+        _ => date(row, n).map(Some),
+    }
+}
+
+/// Reads one row of the eServices UniversalReport. Any cell that's missing or
+/// won't parse fails the row with the column it was in.
+fn parse_attendee(row: &[Data]) -> Result<entity::attendee::Model, String> {
+    let capid = number(row, 1)?;
 
-    StatusCode::OK
+    Ok(entity::attendee::Model {
+        capid,
+        rank: required(row, 5)?.to_string(),
+        last_name: required(row, 6)?.to_string(),
+        first_name: required(row, 7)?.to_string(),
+        middle_name: text(row, 8).map(str::to_string),
+        unit: format!(
+            "{}-{}-{:0>3}",
+            required(row, 9)?,
+            required(row, 10)?,
+            required(row, 11)?
+        ),
+        gender: required(row, 12)?.to_string(),
+        date_of_birth: {
+            let date = required(row, 13)?;
+            NaiveDate::parse_from_str(format!("01/{date}").as_str(), "%d/%m/%y")
+                .map_err(|_| invalid(13, date))?
+        },
+        age_at_start: number(row, 15)?,
+        age_at_end: number(row, 16)?,
+        height: match row.get(17).and_then(|c| c.get_float()) {
+            Some(0.0) => None,
+            None => None,
+            opt => opt.map(|v| v.round() as i32),
+        },
+        weight: match row.get(18).and_then(|c| c.get_float()) {
+            Some(0.0) => None,
+            None => None,
+            opt => opt.map(|v| v.round() as i32),
+        },
+        shirt_size: match text(row, 19) {
+            Some("          ") => None,
+            Some("Unavailable") => None,
+            None => None,
+            opt => opt.map(|s| s.to_string()),
+        },
+        member_type: required(row, 20)?.to_string(),
+        expiration: date(row, 21)?,
+        member_status: required(row, 22)?.to_string(),
+        home_phone: text(row, 23).map(str::to_string),
+        cell_phone: text(row, 24).map(str::to_string),
+        email: required(row, 27)?.to_string(),
+        address1: required(row, 28)?.to_string(),
+        address2: required(row, 29)?.to_string(),
+        city: required(row, 30)?.to_string(),
+        state: required(row, 31)?.to_string(),
+        zip_code: required(row, 32)?.to_string(),
+        registration_status: required(row, 35)?.to_string(),
+        is_staff: matches!(required(row, 36)?, "Yes"),
+        registration_id: number(row, 40)?,
+        comments: text(row, 42).map(str::to_string),
+        emergency_contact_name: text(row, 25).map(str::to_string),
+        emergency_contact_number: text(row, 26).map(str::to_string),
+        cadet_parent_phone_primary: text(row, 53).map(str::to_string),
+        cadet_parent_phone_secondary: text(row, 54).map(str::to_string),
+        cadet_parent_email_primary: text(row, 56).map(str::to_string),
+        cadet_parent_email_secondary: text(row, 57).map(str::to_string),
+        unit_commander_name: required(row, 59)?.to_string(),
+        unit_commander_email: required(row, 60)?.to_string(),
+        wing_commander_name: required(row, 61)?.to_string(),
+        wing_commander_email: required(row, 62)?.to_string(),
+        is_pilot: matches!(required(row, 63)?, "Yes"),
+        dl_expiration: match text(row, 64) {
+            Some(" ") | None => None,
+            Some(_) => Some(date(row, 64)?),
+        },
+        last_encampment: match text(row, 65) {
+            Some(" ") | None => None,
+            Some(_) => Some(date(row, 65)?),
+        },
+        highest_o_ride: match required(row, 66)? {
+            "" => None,
+            val => Some(val.parse().map_err(|_| invalid(66, val))?),
+        },
+        aircraft_ground_handling: completed(row, 68)?,
+        wing_runner: completed(row, 69)?,
+        orm_basic: completed(row, 70)?,
+        orm_intermediate: completed(row, 71)?,
+        cppt_expiration: match required(row, 72)? {
+            "" => None,
+            _ => Some(date(row, 72)?),
+        },
+        monthly_safety: completed(row, 73)?,
+        icut: completed(row, 74)?,
+        is100: completed(row, 75)?,
+        is700: completed(row, 76)?,
+        capt116: completed(row, 77)?,
+        capt117_part1: completed(row, 78)?,
+        capt117_part2: completed(row, 79)?,
+        capt117_part3: completed(row, 80)?,
+        first_aid: completed(row, 81)?,
+        invoice_id: row
+            .get(82)
+            .and_then(|c| c.get_float())
+            .map(|v| v.round() as i32),
+        prices_id: row
+            .get(83)
+            .and_then(|c| c.get_float())
+            .map(|v| v.round() as i32),
+        invoice_status: text(row, 84).map(str::to_string),
+        registered_by: text(row, 87).map(str::to_string),
+        departed_at: None,
+        cap_license_expiration: None,
+    })
 }
 
 async fn create(db: &DbConn, attendee: entity::attendee::Model) -> Result<(), DbErr> {
@@ -321,6 +462,8 @@ async fn create(db: &DbConn, attendee: entity::attendee::Model) -> Result<(), Db
         prices_id: Set(attendee.prices_id),
         invoice_status: Set(attendee.invoice_status),
         registered_by: Set(attendee.registered_by),
+        departed_at: Set(attendee.departed_at),
+        cap_license_expiration: Set(attendee.cap_license_expiration),
     };
 
     entity::attendee::Entity::insert(model)
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use chrono::Local;
+use entity::prelude::{Activity, Attendee, CalendarFeed, Flight, Location, TimeBlock};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, DbConn, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, SqlErr,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::handlers::schedules::{attendee_activities, flight_activities, live_blocks};
+use crate::ical::{self, Calendar, Event};
+
+const ENCAMPMENT: &str = "encampment";
+const FLIGHT: &str = "flight";
+const STAFF: &str = "staff";
+
+#[derive(Deserialize)]
+pub(crate) struct CalendarFeedParameters {
+    scope: String,
+    flight_id: Option<i32>,
+    capid: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct CalendarFeedLink {
+    #[serde(flatten)]
+    feed: entity::calendar_feed::Model,
+    path: String,
+}
+
+fn link(feed: entity::calendar_feed::Model) -> CalendarFeedLink {
+    CalendarFeedLink {
+        path: format!("/calendars/feed/{}.ics", feed.token.simple()),
+        feed,
+    }
+}
+
+pub(crate) async fn get_all_calendar_feeds(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<entity::calendar_feed::ModelEx>>, StatusCode> {
+    let mut query = CalendarFeed::load()
+        .with(Flight)
+        .with(Attendee)
+        .order_by_asc(entity::calendar_feed::Column::CreatedAt);
+
+    if let Some(scope) = params.get("scope") {
+        query = query.filter(entity::calendar_feed::Column::Scope.eq(scope));
+    }
+
+    if let Some(s) = params.get("flightId") {
+        let id: i32 = s.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+        query = query.filter(entity::calendar_feed::Column::FlightId.eq(id));
+    }
+
+    if let Some(s) = params.get("capid") {
+        let capid: i32 = s.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+        query = query.filter(entity::calendar_feed::Column::Capid.eq(capid));
+    }
+
+    match query.all(&state.db).await {
+        Ok(feeds) => Ok(Json(feeds)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Issues a new subscription link. Anyone holding the token can read the
+/// feed, so a leaked link is dealt with by deleting it and issuing another.
+pub(crate) async fn create_calendar_feed(
+    State(state): State<Arc<AppState>>,
+    Json(data): Json<CalendarFeedParameters>,
+) -> Result<Json<CalendarFeedLink>, StatusCode> {
+    let valid = match data.scope.as_str() {
+        ENCAMPMENT => data.flight_id.is_none() && data.capid.is_none(),
+        FLIGHT => data.flight_id.is_some() && data.capid.is_none(),
+        STAFF => data.flight_id.is_none() && data.capid.is_some(),
+        _ => false,
+    };
+    if !valid {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let model = entity::calendar_feed::ActiveModel {
+        id: NotSet,
+        token: Set(Uuid::new_v4()),
+        scope: Set(data.scope),
+        flight_id: Set(data.flight_id),
+        capid: Set(data.capid),
+        created_at: Set(Local::now().naive_local()),
+    };
+
+    match model.insert(&state.db).await {
+        Ok(feed) => Ok(Json(link(feed))),
+        Err(e) => match e.sql_err() {
+            Some(SqlErr::ForeignKeyConstraintViolation(_)) => Err(StatusCode::BAD_REQUEST),
+            _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        },
+    }
+}
+
+pub(crate) async fn delete_calendar_feed(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    match CalendarFeed::delete_by_id(id).exec(&state.db).await {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Builds the calendar for a feed from the approved schedules. Each event's
+/// UID comes from the activity's `uid`, which survives revisions, and its
+/// sequence from the schedule version, so clients update events in place.
+async fn calendar(db: &DbConn, feed: &entity::calendar_feed::Model) -> Result<Calendar, DbErr> {
+    let live = live_blocks(db).await?;
+
+    let (name, ids) = match (feed.flight_id, feed.capid) {
+        (Some(flight_id), _) => {
+            let name = match Flight::find_by_id(flight_id).one(db).await? {
+                Some(flight) => format!("{} Flight", flight.name),
+                None => "Flight".to_string(),
+            };
+            (name, Some(flight_activities(db, flight_id).await?))
+        }
+        (None, Some(capid)) => {
+            let name = match Attendee::find_by_id(capid).one(db).await? {
+                Some(a) => format!("{} {} {}", a.rank, a.first_name, a.last_name),
+                None => capid.to_string(),
+            };
+            (name, Some(attendee_activities(db, capid).await?))
+        }
+        (None, None) => {
+            let mut names: Vec<String> = live.values().map(|s| s.name.clone()).collect();
+            names.sort();
+            names.dedup();
+            let name = match names.len() {
+                1 => names.remove(0),
+                _ => "Encampment".to_string(),
+            };
+            (name, None)
+        }
+    };
+
+    let mut query = Activity::load()
+        .filter(entity::activity::Column::BlockId.is_in(live.keys().copied()))
+        .with(TimeBlock)
+        .with(Location)
+        .with(Flight)
+        .with(Attendee);
+
+    if let Some(ids) = ids {
+        query = query.filter(entity::activity::Column::Id.is_in(ids));
+    }
+
+    let mut calendar = Calendar::new(&format!("{name} Schedule"));
+
+    for activity in query.all(db).await? {
+        let Some(block) = activity.block.as_ref() else {
+            continue;
+        };
+
+        let mut description = vec![];
+        if let Some(text) = &activity.description {
+            description.push(text.clone());
+        }
+        if !activity.flights.is_empty() {
+            let flights: Vec<&str> = activity.flights.iter().map(|f| f.name.as_str()).collect();
+            description.push(format!("Flights: {}", flights.join(", ")));
+        }
+        if !activity.staff.is_empty() {
+            let staff: Vec<String> = activity
+                .staff
+                .iter()
+                .map(|a| format!("{} {}", a.rank, a.last_name))
+                .collect();
+            description.push(format!("Staff: {}", staff.join(", ")));
+        }
+
+        calendar.event(Event {
+            uid: format!("{}@campfire", activity.uid),
+            sequence: live.get(&block.id).map(|s| s.version).unwrap_or(1),
+            starts_at: block.starts_at,
+            ends_at: block.ends_at,
+            summary: &activity.name,
+            location: activity.location.as_ref().map(|l| l.name.as_str()),
+            description: (!description.is_empty()).then(|| description.join("\n")),
+        });
+    }
+
+    Ok(calendar)
+}
+
+/// The subscribed feed itself, `/calendars/feed/{token}.ics`. The token is
+/// the only thing guarding it.
+pub(crate) async fn get_calendar_feed(
+    State(state): State<Arc<AppState>>,
+    Path(file): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let token: Uuid = file
+        .trim_end_matches(".ics")
+        .parse()
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+
+    let feed = match CalendarFeed::find()
+        .filter(entity::calendar_feed::Column::Token.eq(token))
+        .one(&state.db)
+        .await
+    {
+        Ok(Some(feed)) => feed,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let calendar = calendar(&state.db, &feed)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(ical::response(
+        &format!("{}-schedule.ics", feed.scope),
+        calendar.finish(),
+    ))
+}
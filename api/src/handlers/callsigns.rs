@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use entity::prelude::{Attendee, Callsign, Radio};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, DbErr, EntityTrait, IntoActiveModel,
+    QueryFilter, QueryOrder,
+};
+use serde::Deserialize;
+
+use crate::AppState;
+use crate::pdf::{self, Document};
+
+pub(crate) async fn get_all_callsigns(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let callsigns = Callsign::load()
+        .with(Attendee)
+        .order_by_asc(entity::callsign::Column::Name)
+        .all(&state.db)
+        .await
+        .expect("Could not get callsigns");
+
+    Json(callsigns)
+}
+
+pub(crate) async fn create_new_callsign(
+    State(state): State<Arc<AppState>>,
+    Json(callsign): Json<entity::callsign::Model>,
+) -> impl IntoResponse {
+    let active_model = entity::callsign::ActiveModel {
+        id: NotSet,
+        name: Set(callsign.name),
+        position: Set(callsign.position),
+        assigned_to: Set(callsign.assigned_to),
+    };
+
+    match active_model.insert(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(DbErr::RecordNotInserted) => StatusCode::BAD_REQUEST,
+        Err(DbErr::Query(_)) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn delete_callsign(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let id = match params.get("id") {
+        Some(s) => match s.parse() {
+            Ok(v) => v,
+            Err(_) => return StatusCode::BAD_REQUEST,
+        },
+        None => return StatusCode::BAD_REQUEST,
+    };
+
+    let model = entity::callsign::ActiveModel {
+        id: Set(id),
+        ..Default::default()
+    };
+
+    match model.delete(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(DbErr::RecordNotFound(_)) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct AssignCallsignParameters {
+    callsign_id: i32,
+    capid: Option<i32>,
+    position: Option<String>,
+}
+
+pub(crate) async fn assign_callsign(
+    State(state): State<Arc<AppState>>,
+    Json(data): Json<AssignCallsignParameters>,
+) -> impl IntoResponse {
+    if data.capid.is_none() && data.position.is_none() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let mut model = match Callsign::find_by_id(data.callsign_id).one(&state.db).await {
+        Ok(opt) => match opt {
+            Some(model) => model.into_active_model(),
+            None => return StatusCode::BAD_REQUEST,
+        },
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    model.assigned_to = Set(data.capid);
+    if data.position.is_some() {
+        model.position = Set(data.position);
+    }
+
+    match model.update(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(DbErr::Query(_)) => StatusCode::BAD_REQUEST,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn unassign_callsign(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let id: i32 = match params.get("callsignId") {
+        Some(s) => match s.parse() {
+            Ok(v) => v,
+            Err(_) => return StatusCode::BAD_REQUEST,
+        },
+        None => return StatusCode::BAD_REQUEST,
+    };
+
+    let mut model = match Callsign::find_by_id(id).one(&state.db).await {
+        Ok(opt) => match opt {
+            Some(model) => model.into_active_model(),
+            None => return StatusCode::BAD_REQUEST,
+        },
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    model.assigned_to = Set(None);
+
+    match model.update(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn get_net_roster(
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let callsigns = Callsign::load()
+        .with(Attendee)
+        .order_by_asc(entity::callsign::Column::Name)
+        .all(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let radios = Radio::find()
+        .filter(entity::radio::Column::IssuedTo.is_not_null())
+        .all(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut tags: HashMap<i32, Vec<String>> = HashMap::new();
+    for radio in radios {
+        if let Some(capid) = radio.issued_to {
+            tags.entry(capid)
+                .or_default()
+                .push(radio.property_tag_number);
+        }
+    }
+
+    let rows: Vec<Vec<String>> = callsigns
+        .into_iter()
+        .map(|callsign| {
+            let (name, capid, radios) = match callsign.holder.into_option() {
+                Some(holder) => (
+                    format!(
+                        "{} {}, {}",
+                        holder.rank, holder.last_name, holder.first_name
+                    ),
+                    holder.capid.to_string(),
+                    tags.get(&holder.capid)
+                        .map(|t| t.join(", "))
+                        .unwrap_or_default(),
+                ),
+                None => ("Unassigned".to_string(), String::new(), String::new()),
+            };
+
+            vec![
+                callsign.name,
+                callsign.position.unwrap_or_default(),
+                name,
+                capid,
+                radios,
+            ]
+        })
+        .collect();
+
+    let mut document = Document::new("Net Roster");
+    document.table(
+        &[
+            ("Callsign", 0.22),
+            ("Position", 0.22),
+            ("Name", 0.26),
+            ("CAPID", 0.1),
+            ("Radio", 0.2),
+        ],
+        &rows,
+    );
+
+    Ok(pdf::response("net-roster.pdf", document.finish()))
+}
@@ -0,0 +1,295 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::Json;
+use entity::prelude::{
+    Activity, Attendee, Flight, FlightMember, Location, Schedule, ScheduleDay, TimeBlock,
+};
+use sea_orm::{ColumnTrait, DbConn, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use serde::Serialize;
+
+use crate::AppState;
+use crate::handlers::attendees::is_senior_member;
+
+const ERROR: &str = "error";
+const WARNING: &str = "warning";
+
+#[derive(Serialize)]
+pub(crate) struct Conflict {
+    severity: &'static str,
+    kind: &'static str,
+    message: String,
+    activities: Vec<i32>,
+}
+
+impl Conflict {
+    fn error(kind: &'static str, message: String, activities: Vec<i32>) -> Self {
+        Conflict {
+            severity: ERROR,
+            kind,
+            message,
+            activities,
+        }
+    }
+
+    fn warning(kind: &'static str, message: String, activities: Vec<i32>) -> Self {
+        Conflict {
+            severity: WARNING,
+            kind,
+            message,
+            activities,
+        }
+    }
+
+    pub(crate) fn is_error(&self) -> bool {
+        self.severity == ERROR
+    }
+}
+
+struct Slot {
+    activity: entity::activity::ModelEx,
+    block: entity::time_block::Model,
+    people: BTreeSet<i32>,
+}
+
+impl Slot {
+    fn overlaps(&self, other: &Slot) -> bool {
+        self.block.starts_at < other.block.ends_at && other.block.starts_at < self.block.ends_at
+    }
+
+    fn when(&self) -> String {
+        format!(
+            "{} {}-{}",
+            self.block.starts_at.format("%d %b"),
+            self.block.starts_at.format("%H%M"),
+            self.block.ends_at.format("%H%M")
+        )
+    }
+}
+
+/// Checks a schedule for double bookings, rooms over capacity and cadet
+/// activities without two-deep leadership. Errors hold up approval; warnings
+/// are for the scheduler to look at.
+pub(crate) async fn find_conflicts(db: &DbConn, schedule_id: i32) -> Result<Vec<Conflict>, DbErr> {
+    let days = ScheduleDay::find()
+        .filter(entity::schedule_day::Column::ScheduleId.eq(schedule_id))
+        .all(db)
+        .await?;
+
+    let blocks: HashMap<i32, entity::time_block::Model> = TimeBlock::find()
+        .filter(entity::time_block::Column::DayId.is_in(days.iter().map(|d| d.id)))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|b| (b.id, b))
+        .collect();
+
+    let activities = Activity::load()
+        .filter(entity::activity::Column::BlockId.is_in(blocks.keys().copied()))
+        .with(Location)
+        .with(Flight)
+        .with(Attendee)
+        .order_by_asc(entity::activity::Column::Id)
+        .all(db)
+        .await?;
+
+    let mut members: HashMap<i32, Vec<i32>> = HashMap::new();
+    for member in FlightMember::find()
+        .filter(
+            entity::flight_member::Column::FlightId.is_in(
+                activities
+                    .iter()
+                    .flat_map(|a| a.flights.iter().map(|f| f.id)),
+            ),
+        )
+        .all(db)
+        .await?
+    {
+        members
+            .entry(member.flight_id)
+            .or_default()
+            .push(member.capid);
+    }
+
+    let mut slots = vec![];
+    for activity in activities {
+        let Some(block) = blocks.get(&activity.block_id).cloned() else {
+            continue;
+        };
+
+        let mut people: BTreeSet<i32> = activity.staff.iter().map(|a| a.capid).collect();
+        for flight in activity.flights.iter() {
+            people.extend(members.get(&flight.id).into_iter().flatten());
+        }
+
+        slots.push(Slot {
+            activity,
+            block,
+            people,
+        });
+    }
+
+    let attendees: HashMap<i32, entity::attendee::Model> = Attendee::find()
+        .filter(
+            entity::attendee::Column::Capid
+                .is_in(slots.iter().flat_map(|s| s.people.iter().copied())),
+        )
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|a| (a.capid, a))
+        .collect();
+
+    let name = |capid: &i32| match attendees.get(capid) {
+        Some(a) => format!("{} {}", a.rank, a.last_name),
+        None => capid.to_string(),
+    };
+
+    let mut conflicts = vec![];
+
+    for (i, a) in slots.iter().enumerate() {
+        for b in slots[i + 1..].iter().filter(|b| a.overlaps(b)) {
+            let pair = vec![a.activity.id, b.activity.id];
+
+            if let Some(location) = a.activity.location.as_ref()
+                && a.activity.location_id == b.activity.location_id
+            {
+                conflicts.push(Conflict::error(
+                    "location_double_booked",
+                    format!(
+                        "{} is booked for {} ({}) and {} ({})",
+                        location.name,
+                        a.activity.name,
+                        a.when(),
+                        b.activity.name,
+                        b.when()
+                    ),
+                    pair.clone(),
+                ));
+            }
+
+            for staff in a.activity.staff.iter() {
+                if b.activity.staff.iter().any(|s| s.capid == staff.capid) {
+                    conflicts.push(Conflict::error(
+                        "staff_double_booked",
+                        format!(
+                            "{} is staffing both {} ({}) and {} ({})",
+                            name(&staff.capid),
+                            a.activity.name,
+                            a.when(),
+                            b.activity.name,
+                            b.when()
+                        ),
+                        pair.clone(),
+                    ));
+                }
+            }
+
+            for flight in a.activity.flights.iter() {
+                if b.activity.flights.iter().any(|f| f.id == flight.id) {
+                    conflicts.push(Conflict::error(
+                        "flight_double_booked",
+                        format!(
+                            "{} Flight is scheduled for both {} ({}) and {} ({})",
+                            flight.name,
+                            a.activity.name,
+                            a.when(),
+                            b.activity.name,
+                            b.when()
+                        ),
+                        pair.clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    for slot in slots.iter() {
+        let id = vec![slot.activity.id];
+
+        if let Some(location) = slot.activity.location.as_ref() {
+            match location.capacity {
+                Some(capacity) if slot.people.len() as i32 > capacity => {
+                    conflicts.push(Conflict::error(
+                        "over_capacity",
+                        format!(
+                            "{} ({}) has {} people but {} holds {capacity}",
+                            slot.activity.name,
+                            slot.when(),
+                            slot.people.len(),
+                            location.name
+                        ),
+                        id.clone(),
+                    ));
+                }
+                None if !slot.people.is_empty() => {
+                    conflicts.push(Conflict::warning(
+                        "capacity_unknown",
+                        format!(
+                            "{} has no capacity set, so {} ({}) can't be checked",
+                            location.name,
+                            slot.activity.name,
+                            slot.when()
+                        ),
+                        id.clone(),
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        // Youth protection: any cadet activity needs two senior members with
+        // CPPT current through the end of the block
+        let cadets = slot
+            .people
+            .iter()
+            .filter_map(|capid| attendees.get(capid))
+            .filter(|a| !is_senior_member(a))
+            .count();
+
+        if cadets > 0 {
+            let seniors = slot
+                .people
+                .iter()
+                .filter_map(|capid| attendees.get(capid))
+                .filter(|a| is_senior_member(a))
+                .filter(|a| {
+                    a.cppt_expiration
+                        .is_some_and(|d| d >= slot.block.ends_at.date())
+                })
+                .count();
+
+            if seniors < 2 {
+                conflicts.push(Conflict::error(
+                    "two_deep_leadership",
+                    format!(
+                        "{} ({}) has {cadets} cadets but {seniors} CPPT-current senior members",
+                        slot.activity.name,
+                        slot.when()
+                    ),
+                    id,
+                ));
+            }
+        }
+    }
+
+    Ok(conflicts)
+}
+
+pub(crate) async fn get_schedule_conflicts(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<Conflict>>, StatusCode> {
+    match Schedule::find_by_id(id).one(&state.db).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+
+    match find_conflicts(&state.db, id).await {
+        Ok(conflicts) => Ok(Json(conflicts)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
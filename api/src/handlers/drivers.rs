@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use chrono::{Local, NaiveDate};
+use entity::prelude::{Attendee, DriverOverride, Vehicle};
+use sea_orm::{ActiveModelTrait, ActiveValue::*, DbConn, EntityTrait, IntoActiveModel, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+use crate::AppState;
+use crate::config::{DriverRules, OrmLevel};
+use crate::handlers::attendees::{COMMANDER, has_role, is_senior_member};
+
+#[derive(Deserialize)]
+pub(crate) struct Override {
+    override_by: i32,
+    override_reason: String,
+}
+
+#[derive(Serialize)]
+pub(crate) struct Eligibility {
+    eligible: bool,
+    reasons: Vec<String>,
+}
+
+pub(crate) fn refuse(reasons: Vec<String>) -> Response {
+    refuse_with(StatusCode::UNPROCESSABLE_ENTITY, reasons)
+}
+
+pub(crate) fn refuse_with(status: StatusCode, reasons: Vec<String>) -> Response {
+    (
+        status,
+        Json(Eligibility {
+            eligible: false,
+            reasons,
+        }),
+    )
+        .into_response()
+}
+
+fn ineligibility(
+    rules: &DriverRules,
+    attendee: &entity::attendee::Model,
+    trip_date: NaiveDate,
+) -> Vec<String> {
+    let mut reasons = vec![];
+
+    if rules.require_senior && !is_senior_member(attendee) {
+        reasons.push("Driver must be a senior member".to_string());
+    }
+
+    match attendee.dl_expiration {
+        None => reasons.push("No state driver's license on file".to_string()),
+        Some(date) if date < trip_date => reasons.push(format!(
+            "State driver's license expires {date} before the trip ends"
+        )),
+        Some(_) => {}
+    }
+
+    match rules.required_orm {
+        OrmLevel::None => {}
+        OrmLevel::Basic => {
+            if attendee.orm_basic.is_none() {
+                reasons.push("Basic ORM training not complete".to_string());
+            }
+        }
+        OrmLevel::Intermediate => {
+            if attendee.orm_intermediate.is_none() {
+                reasons.push("Intermediate ORM training not complete".to_string());
+            }
+        }
+    }
+
+    if rules.require_cap_license {
+        match attendee.cap_license_expiration {
+            None => reasons.push("No CAP driver's license on file".to_string()),
+            Some(date) if date < trip_date => reasons.push(format!(
+                "CAP driver's license expires {date} before the trip ends"
+            )),
+            Some(_) => {}
+        }
+    }
+
+    reasons
+}
+
+/// Checks the driver against the configured rules. A driver who doesn't
+/// qualify can still go with a commander's override, which comes back to be
+/// logged alongside the issue itself.
+pub(crate) async fn check(
+    db: &DbConn,
+    rules: &DriverRules,
+    vehicle_id: i32,
+    capid: i32,
+    trip_date: NaiveDate,
+    approval: Option<Override>,
+) -> Result<Option<entity::driver_override::ActiveModel>, Response> {
+    let driver = match Attendee::find_by_id(capid).one(db).await {
+        Ok(Some(driver)) => driver,
+        Ok(None) => return Err(StatusCode::BAD_REQUEST.into_response()),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response()),
+    };
+
+    let reasons = ineligibility(rules, &driver, trip_date);
+    if reasons.is_empty() {
+        return Ok(None);
+    }
+
+    let approval = match approval {
+        Some(approval) if !approval.override_reason.trim().is_empty() => approval,
+        _ => {
+            return Err(refuse(reasons));
+        }
+    };
+
+    match has_role(db, approval.override_by, &[COMMANDER]).await {
+        Ok(true) => {}
+        Ok(false) => return Err(StatusCode::FORBIDDEN.into_response()),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response()),
+    }
+
+    Ok(Some(entity::driver_override::ActiveModel {
+        id: NotSet,
+        vehicle_id: Set(vehicle_id),
+        driver_capid: Set(capid),
+        approved_by: Set(approval.override_by),
+        reasons: Set(reasons.join("\n")),
+        justification: Set(approval.override_reason),
+        created_at: Set(Local::now().naive_local()),
+    }))
+}
+
+pub(crate) async fn get_driver_eligibility(
+    State(state): State<Arc<AppState>>,
+    Path(capid): Path<i32>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Eligibility>, StatusCode> {
+    let trip_date = match params.get("date") {
+        Some(s) => NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| StatusCode::BAD_REQUEST)?,
+        None => Local::now().date_naive(),
+    };
+
+    let driver = match Attendee::find_by_id(capid).one(&state.db).await {
+        Ok(Some(driver)) => driver,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let reasons = ineligibility(&state.config.driver, &driver, trip_date);
+
+    Ok(Json(Eligibility {
+        eligible: reasons.is_empty(),
+        reasons,
+    }))
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CapLicenseParameters {
+    cap_license_expiration: Option<NaiveDate>,
+}
+
+pub(crate) async fn set_cap_license(
+    State(state): State<Arc<AppState>>,
+    Path(capid): Path<i32>,
+    Json(data): Json<CapLicenseParameters>,
+) -> impl IntoResponse {
+    let mut model = match Attendee::find_by_id(capid).one(&state.db).await {
+        Ok(Some(model)) => model.into_active_model(),
+        Ok(None) => return StatusCode::BAD_REQUEST,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    model.cap_license_expiration = Set(data.cap_license_expiration);
+
+    match model.update(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn get_all_driver_overrides(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let overrides = DriverOverride::load()
+        .with(Vehicle)
+        .with(Attendee)
+        .order_by_desc(entity::driver_override::Column::CreatedAt)
+        .all(&state.db)
+        .await
+        .expect("Could not get driver overrides");
+
+    Json(overrides)
+}
@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Multipart, Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use chrono::Local;
+use entity::prelude::{
+    FacilityRequest, FacilityRequestComment, FacilityRequestPhoto, FacilityRequestStatus, Location,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, DbConn, DbErr, EntityLoaderTrait, EntityTrait,
+    IntoActiveModel, QueryFilter, QueryOrder, SqlErr, TransactionTrait,
+};
+use serde::Deserialize;
+
+use crate::AppState;
+use crate::handlers::uploads::save_photos;
+
+const OPEN: &str = "open";
+const IN_PROGRESS: &str = "in_progress";
+const ON_HOLD: &str = "on_hold";
+const RESOLVED: &str = "resolved";
+const CLOSED: &str = "closed";
+
+const NORMAL: &str = "normal";
+
+fn is_status(status: &str) -> bool {
+    matches!(status, OPEN | IN_PROGRESS | ON_HOLD | RESOLVED | CLOSED)
+}
+
+fn is_priority(priority: &str) -> bool {
+    matches!(priority, "low" | NORMAL | "high" | "urgent")
+}
+
+fn status(e: DbErr) -> StatusCode {
+    match e.sql_err() {
+        Some(SqlErr::ForeignKeyConstraintViolation(_)) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct NewFacilityRequestParameters {
+    category: String,
+    location_id: Option<i32>,
+    description: String,
+    requested_by: i32,
+    priority: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct FacilityRequestParameters {
+    category: String,
+    location_id: Option<i32>,
+    description: String,
+    priority: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct FacilityStatusParameters {
+    status: String,
+    capid: Option<i32>,
+    note: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct AssignParameters {
+    assigned_to: Option<i32>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CommentParameters {
+    capid: i32,
+    body: String,
+}
+
+/// Requests, newest first. Filters on `?status=`, `?category=`,
+/// `?priority=`, `?locationId=`, `?assignedTo=` and `?requestedBy=`, and
+/// `?open=true` for anything not yet resolved or closed.
+pub(crate) async fn get_all_facility_requests(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<entity::facility_request::ModelEx>>, StatusCode> {
+    let mut query = FacilityRequest::load()
+        .with(Location)
+        .order_by_desc(entity::facility_request::Column::CreatedAt);
+
+    if let Some(status) = params.get("status") {
+        query = query.filter(entity::facility_request::Column::Status.eq(status));
+    }
+
+    if let Some(category) = params.get("category") {
+        query = query
+            .filter(entity::facility_request::Column::Category.eq(category.trim().to_lowercase()));
+    }
+
+    if let Some(priority) = params.get("priority") {
+        query = query.filter(entity::facility_request::Column::Priority.eq(priority));
+    }
+
+    if params.get("open").is_some_and(|s| s == "true") {
+        query =
+            query.filter(entity::facility_request::Column::Status.is_not_in([RESOLVED, CLOSED]));
+    }
+
+    for (param, column) in [
+        ("locationId", entity::facility_request::Column::LocationId),
+        ("assignedTo", entity::facility_request::Column::AssignedTo),
+        ("requestedBy", entity::facility_request::Column::RequestedBy),
+    ] {
+        if let Some(s) = params.get(param) {
+            let id: i32 = s.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+            query = query.filter(column.eq(id));
+        }
+    }
+
+    match query.all(&state.db).await {
+        Ok(requests) => Ok(Json(requests)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn create_facility_request(
+    State(state): State<Arc<AppState>>,
+    Json(data): Json<NewFacilityRequestParameters>,
+) -> Result<Json<entity::facility_request::Model>, StatusCode> {
+    let priority = data.priority.unwrap_or_else(|| NORMAL.to_string());
+    if data.category.trim().is_empty()
+        || data.description.trim().is_empty()
+        || !is_priority(&priority)
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let now = Local::now().naive_local();
+
+    let txn = state
+        .db
+        .begin()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let request = entity::facility_request::ActiveModel {
+        id: NotSet,
+        category: Set(data.category.trim().to_lowercase()),
+        location_id: Set(data.location_id),
+        description: Set(data.description.trim().to_string()),
+        requested_by: Set(data.requested_by),
+        priority: Set(priority),
+        status: Set(OPEN.to_string()),
+        assigned_to: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+    }
+    .insert(&txn)
+    .await
+    .map_err(status)?;
+
+    entity::facility_request_status::ActiveModel {
+        id: NotSet,
+        request_id: Set(request.id),
+        from_status: Set(None),
+        to_status: Set(OPEN.to_string()),
+        changed_by: Set(Some(data.requested_by)),
+        note: Set(None),
+        changed_at: Set(now),
+    }
+    .insert(&txn)
+    .await
+    .map_err(status)?;
+
+    match txn.commit().await {
+        Ok(_) => Ok(Json(request)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn get_facility_request(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<entity::facility_request::ModelEx>, StatusCode> {
+    match FacilityRequest::load()
+        .filter_by_id(id)
+        .with(Location)
+        .with(FacilityRequestComment)
+        .with(FacilityRequestPhoto)
+        .with(FacilityRequestStatus)
+        .one(&state.db)
+        .await
+    {
+        Ok(Some(request)) => Ok(Json(request)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn find_open(db: &DbConn, id: i32) -> Result<entity::facility_request::Model, StatusCode> {
+    match FacilityRequest::find_by_id(id).one(db).await {
+        Ok(Some(request)) if request.status == CLOSED => Err(StatusCode::CONFLICT),
+        Ok(Some(request)) => Ok(request),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn update_facility_request(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<FacilityRequestParameters>,
+) -> impl IntoResponse {
+    if data.category.trim().is_empty()
+        || data.description.trim().is_empty()
+        || !is_priority(&data.priority)
+    {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let mut model = match find_open(&state.db, id).await {
+        Ok(request) => request.into_active_model(),
+        Err(status) => return status,
+    };
+
+    model.category = Set(data.category.trim().to_lowercase());
+    model.location_id = Set(data.location_id);
+    model.description = Set(data.description.trim().to_string());
+    model.priority = Set(data.priority);
+    model.updated_at = Set(Local::now().naive_local());
+
+    match model.update(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(e) => status(e),
+    }
+}
+
+/// Moves a request to a new status and records who did it. Closed requests
+/// stay closed; a resolved one can be reopened if the fix didn't hold.
+pub(crate) async fn set_facility_request_status(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<FacilityStatusParameters>,
+) -> impl IntoResponse {
+    if !is_status(&data.status) {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let request = match find_open(&state.db, id).await {
+        Ok(request) => request,
+        Err(status) => return status,
+    };
+
+    if request.status == data.status {
+        return StatusCode::CONFLICT;
+    }
+
+    let now = Local::now().naive_local();
+
+    let txn = match state.db.begin().await {
+        Ok(txn) => txn,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    let change = entity::facility_request_status::ActiveModel {
+        id: NotSet,
+        request_id: Set(id),
+        from_status: Set(Some(request.status.clone())),
+        to_status: Set(data.status.clone()),
+        changed_by: Set(data.capid),
+        note: Set(data
+            .note
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())),
+        changed_at: Set(now),
+    };
+
+    if let Err(e) = change.insert(&txn).await {
+        return status(e);
+    }
+
+    let mut model = request.into_active_model();
+    model.status = Set(data.status);
+    model.updated_at = Set(now);
+
+    if let Err(e) = model.update(&txn).await {
+        return status(e);
+    }
+
+    match txn.commit().await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Hands a request to someone, or takes it back with `null`.
+pub(crate) async fn assign_facility_request(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<AssignParameters>,
+) -> impl IntoResponse {
+    let mut model = match find_open(&state.db, id).await {
+        Ok(request) => request.into_active_model(),
+        Err(status) => return status,
+    };
+
+    model.assigned_to = Set(data.assigned_to);
+    model.updated_at = Set(Local::now().naive_local());
+
+    match model.update(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(e) => status(e),
+    }
+}
+
+pub(crate) async fn add_facility_request_comment(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<CommentParameters>,
+) -> Result<Json<entity::facility_request_comment::Model>, StatusCode> {
+    if data.body.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    match FacilityRequest::find_by_id(id).one(&state.db).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+
+    let model = entity::facility_request_comment::ActiveModel {
+        id: NotSet,
+        request_id: Set(id),
+        capid: Set(data.capid),
+        body: Set(data.body.trim().to_string()),
+        created_at: Set(Local::now().naive_local()),
+    };
+
+    model.insert(&state.db).await.map(Json).map_err(status)
+}
+
+pub(crate) async fn upload_facility_request_photos(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    match FacilityRequest::find_by_id(id).one(&state.db).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    let photos = match save_photos(&mut multipart, &format!("facility_{id}")).await {
+        Ok(photos) => photos,
+        Err(status) => return status,
+    };
+
+    let photos = photos
+        .into_iter()
+        .map(|photo| entity::facility_request_photo::ActiveModel {
+            id: NotSet,
+            request_id: Set(id),
+            file_name: Set(photo.file_name),
+            content_type: Set(photo.content_type),
+            uploaded_at: Set(Local::now().naive_local()),
+        });
+
+    match FacilityRequestPhoto::insert_many(photos)
+        .exec(&state.db)
+        .await
+    {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn delete_facility_request(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    match FacilityRequest::delete_by_id(id).exec(&state.db).await {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
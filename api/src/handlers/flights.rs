@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use entity::prelude::{Attendee, Flight, FlightMember};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, DbErr, EntityLoaderTrait, EntityTrait,
+    QueryFilter, QueryOrder, SqlErr,
+};
+use serde::Deserialize;
+
+use crate::AppState;
+
+#[derive(Deserialize)]
+pub(crate) struct FlightParameters {
+    name: String,
+    squadron: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct FlightMemberParameters {
+    capid: i32,
+}
+
+fn status(e: DbErr) -> StatusCode {
+    match e.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => StatusCode::CONFLICT,
+        Some(SqlErr::ForeignKeyConstraintViolation(_)) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn get_all_flights(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<entity::flight::ModelEx>>, StatusCode> {
+    match Flight::load()
+        .with(Attendee)
+        .order_by_asc(entity::flight::Column::Squadron)
+        .order_by_asc(entity::flight::Column::Name)
+        .all(&state.db)
+        .await
+    {
+        Ok(flights) => Ok(Json(flights)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn create_flight(
+    State(state): State<Arc<AppState>>,
+    Json(data): Json<FlightParameters>,
+) -> Result<Json<entity::flight::Model>, StatusCode> {
+    if data.name.trim().is_empty() || data.squadron.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let model = entity::flight::ActiveModel {
+        id: NotSet,
+        name: Set(data.name.trim().to_string()),
+        squadron: Set(data.squadron.trim().to_string()),
+    };
+
+    model.insert(&state.db).await.map(Json).map_err(status)
+}
+
+pub(crate) async fn get_flight(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<entity::flight::ModelEx>, StatusCode> {
+    match Flight::load()
+        .filter_by_id(id)
+        .with(Attendee)
+        .one(&state.db)
+        .await
+    {
+        Ok(Some(flight)) => Ok(Json(flight)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn update_flight(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<FlightParameters>,
+) -> impl IntoResponse {
+    if data.name.trim().is_empty() || data.squadron.trim().is_empty() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let model = entity::flight::ActiveModel {
+        id: Unchanged(id),
+        name: Set(data.name.trim().to_string()),
+        squadron: Set(data.squadron.trim().to_string()),
+    };
+
+    match model.update(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(DbErr::RecordNotUpdated) => StatusCode::NOT_FOUND,
+        Err(e) => status(e),
+    }
+}
+
+pub(crate) async fn delete_flight(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    match Flight::delete_by_id(id).exec(&state.db).await {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// An attendee belongs to one flight at a time; moving them means removing
+/// them from the old flight first.
+pub(crate) async fn add_flight_member(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<FlightMemberParameters>,
+) -> impl IntoResponse {
+    let model = entity::flight_member::ActiveModel {
+        id: NotSet,
+        flight_id: Set(id),
+        capid: Set(data.capid),
+    };
+
+    match model.insert(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(e) => status(e),
+    }
+}
+
+pub(crate) async fn remove_flight_member(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let capid: i32 = match params.get("capid") {
+        Some(s) => match s.parse() {
+            Ok(v) => v,
+            Err(_) => return StatusCode::BAD_REQUEST,
+        },
+        None => return StatusCode::BAD_REQUEST,
+    };
+
+    match FlightMember::delete_many()
+        .filter(entity::flight_member::Column::FlightId.eq(id))
+        .filter(entity::flight_member::Column::Capid.eq(capid))
+        .exec(&state.db)
+        .await
+    {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
@@ -1,18 +1,24 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
 
 use axum::extract::{Json, Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
+use chrono::Local;
 use entity::headcount_entry;
-use entity::prelude::{Attendee, Headcount, HeadcountEntry};
+use entity::prelude::{
+    Activity, Attendee, Flight, FlightMember, Headcount, HeadcountEntry, HeadcountFlight, Location,
+    Schedule, ScheduleDay, TimeBlock,
+};
 use sea_orm::EntityLoaderTrait;
 use sea_orm::{
     ActiveModelTrait,
-    ActiveValue::{NotSet, Set},
-    ColumnTrait, DbErr, EntityTrait, ExprTrait, QueryFilter,
+    ActiveValue::{NotSet, Set, Unchanged},
+    ColumnTrait, ConnectionTrait, DbConn, DbErr, EntityTrait, ExprTrait, PaginatorTrait,
+    QueryFilter,
 };
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::AppState;
 
@@ -32,6 +38,7 @@ pub(crate) async fn get_headcount_by_id(
     let result = entity::headcount::Entity::load()
         .filter_by_id(capid)
         .with(Attendee)
+        .with(Flight)
         .one(&state.db)
         .await;
 
@@ -69,6 +76,8 @@ pub(crate) async fn create_headcount(
         name: Set(headcount.0.name),
         location: Set(headcount.0.location),
         created_at: Set(headcount.0.created_at),
+        activity_uid: Set(None),
+        opens_at: Set(headcount.0.opens_at),
     };
 
     match active_model.insert(&state.db).await {
@@ -79,6 +88,7 @@ pub(crate) async fn create_headcount(
 }
 
 #[derive(Serialize, Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
 pub(crate) struct CAPID {
     capid: i32,
 }
@@ -88,6 +98,16 @@ pub(crate) async fn add_to_headcount(
     Path(headcount_id): Path<i32>,
     Json(data): Json<CAPID>,
 ) -> impl IntoResponse {
+    // Headcounts from the schedule can't be taken before their block starts
+    match Headcount::find_by_id(headcount_id).one(&state.db).await {
+        Ok(Some(headcount)) if headcount.opens_at > Some(Local::now().naive_local()) => {
+            return StatusCode::CONFLICT;
+        }
+        Ok(Some(_)) => {}
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
     let active_model = entity::headcount_entry::ActiveModel {
         id: NotSet,
         headcount_id: Set(headcount_id),
@@ -129,3 +149,227 @@ pub(crate) async fn remove_from_headcount(
         _ => StatusCode::INTERNAL_SERVER_ERROR,
     }
 }
+
+#[derive(Serialize)]
+pub(crate) struct Accountability {
+    headcount_id: i32,
+    pub(crate) activity_uid: Option<Uuid>,
+    pub(crate) expected: usize,
+    pub(crate) accounted: usize,
+    missing: Vec<i32>,
+}
+
+/// Checks headcounts against the members of their flights who haven't
+/// departed. Anyone counted who isn't on the roster doesn't count towards
+/// completion.
+pub(crate) async fn accountability(
+    db: &DbConn,
+    headcounts: &[entity::headcount::Model],
+) -> Result<Vec<Accountability>, DbErr> {
+    let ids: Vec<i32> = headcounts.iter().map(|h| h.id).collect();
+
+    let links = HeadcountFlight::find()
+        .filter(entity::headcount_flight::Column::HeadcountId.is_in(ids.clone()))
+        .all(db)
+        .await?;
+
+    let mut members: HashMap<i32, Vec<i32>> = HashMap::new();
+    for (member, attendee) in FlightMember::find()
+        .filter(entity::flight_member::Column::FlightId.is_in(links.iter().map(|l| l.flight_id)))
+        .find_also_related(Attendee)
+        .all(db)
+        .await?
+    {
+        if attendee.is_some_and(|a| a.departed_at.is_none()) {
+            members
+                .entry(member.flight_id)
+                .or_default()
+                .push(member.capid);
+        }
+    }
+
+    let mut counted: HashMap<i32, HashSet<i32>> = HashMap::new();
+    for entry in HeadcountEntry::find()
+        .filter(headcount_entry::Column::HeadcountId.is_in(ids))
+        .all(db)
+        .await?
+    {
+        counted
+            .entry(entry.headcount_id)
+            .or_default()
+            .insert(entry.capid);
+    }
+
+    let mut roster: HashMap<i32, BTreeSet<i32>> = HashMap::new();
+    for link in links {
+        roster
+            .entry(link.headcount_id)
+            .or_default()
+            .extend(members.get(&link.flight_id).into_iter().flatten());
+    }
+
+    Ok(headcounts
+        .iter()
+        .map(|headcount| {
+            let expected = roster.remove(&headcount.id).unwrap_or_default();
+            let counted = counted.remove(&headcount.id).unwrap_or_default();
+            let missing: Vec<i32> = expected
+                .iter()
+                .filter(|capid| !counted.contains(capid))
+                .copied()
+                .collect();
+
+            Accountability {
+                headcount_id: headcount.id,
+                activity_uid: headcount.activity_uid,
+                expected: expected.len(),
+                accounted: expected.len() - missing.len(),
+                missing,
+            }
+        })
+        .collect())
+}
+
+pub(crate) async fn get_headcount_accountability(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<Accountability>, StatusCode> {
+    let headcount = match Headcount::find_by_id(id).one(&state.db).await {
+        Ok(Some(headcount)) => headcount,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    match accountability(&state.db, &[headcount]).await {
+        Ok(mut list) => Ok(Json(list.remove(0))),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Brings the headcounts for a newly approved schedule in line with its
+/// activities. Each activity marked for a headcount gets one, found again by
+/// the activity's `uid` so a revision updates it rather than starting over,
+/// opening when its block starts and expecting its flights. Headcounts for
+/// activities no longer marked are dropped, unless someone's been counted.
+pub(crate) async fn sync_headcounts<C: ConnectionTrait>(
+    db: &C,
+    schedule: &entity::schedule::Model,
+) -> Result<(), DbErr> {
+    let series: Vec<i32> = Schedule::find()
+        .filter(entity::schedule::Column::SeriesId.eq(schedule.series_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|s| s.id)
+        .collect();
+
+    let days: HashMap<i32, i32> = ScheduleDay::find()
+        .filter(entity::schedule_day::Column::ScheduleId.is_in(series))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|d| (d.id, d.schedule_id))
+        .collect();
+
+    let blocks: HashMap<i32, entity::time_block::Model> = TimeBlock::find()
+        .filter(entity::time_block::Column::DayId.is_in(days.keys().copied()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|b| (b.id, b))
+        .collect();
+
+    let activities = Activity::load()
+        .filter(entity::activity::Column::BlockId.is_in(blocks.keys().copied()))
+        .with(Location)
+        .with(Flight)
+        .all(db)
+        .await?;
+
+    let mut kept = HashSet::new();
+    let mut stale = HashSet::new();
+
+    for activity in activities {
+        let block = &blocks[&activity.block_id];
+        if days[&block.day_id] != schedule.id || !activity.headcount {
+            stale.insert(activity.uid);
+            continue;
+        }
+        kept.insert(activity.uid);
+
+        let name = format!("{} {}", activity.name, block.starts_at.format("%d %b %H%M"));
+        let location = match activity.location.as_ref() {
+            Some(location) => location.name.clone(),
+            None => String::new(),
+        };
+
+        let existing = Headcount::find()
+            .filter(entity::headcount::Column::ActivityUid.eq(activity.uid))
+            .one(db)
+            .await?;
+
+        let headcount = match existing {
+            Some(headcount) => {
+                entity::headcount::ActiveModel {
+                    id: Unchanged(headcount.id),
+                    name: Set(name),
+                    location: Set(location),
+                    created_at: NotSet,
+                    activity_uid: NotSet,
+                    opens_at: Set(Some(block.starts_at)),
+                }
+                .update(db)
+                .await?
+            }
+            None => {
+                entity::headcount::ActiveModel {
+                    id: NotSet,
+                    name: Set(name),
+                    location: Set(location),
+                    created_at: Set(Local::now().naive_local()),
+                    activity_uid: Set(Some(activity.uid)),
+                    opens_at: Set(Some(block.starts_at)),
+                }
+                .insert(db)
+                .await?
+            }
+        };
+
+        HeadcountFlight::delete_many()
+            .filter(entity::headcount_flight::Column::HeadcountId.eq(headcount.id))
+            .exec(db)
+            .await?;
+
+        if !activity.flights.is_empty() {
+            HeadcountFlight::insert_many(activity.flights.iter().map(|flight| {
+                entity::headcount_flight::ActiveModel {
+                    id: NotSet,
+                    headcount_id: Set(headcount.id),
+                    flight_id: Set(flight.id),
+                }
+            }))
+            .exec(db)
+            .await?;
+        }
+    }
+
+    for headcount in Headcount::find()
+        .filter(
+            entity::headcount::Column::ActivityUid
+                .is_in(stale.difference(&kept).copied().collect::<Vec<_>>()),
+        )
+        .all(db)
+        .await?
+    {
+        let counted = HeadcountEntry::find()
+            .filter(headcount_entry::Column::HeadcountId.eq(headcount.id))
+            .count(db)
+            .await?;
+
+        if counted == 0 {
+            Headcount::delete_by_id(headcount.id).exec(db).await?;
+        }
+    }
+
+    Ok(())
+}
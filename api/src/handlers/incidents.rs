@@ -0,0 +1,472 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use axum::extract::{Multipart, Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use chrono::{Local, NaiveDateTime};
+use entity::prelude::{
+    Attendee, Vehicle, VehicleIncident, VehicleIncidentPhoto, VehicleIncidentWitness, VehicleTrip,
+    VehicleType,
+};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, Condition, DbErr, EntityLoaderTrait,
+    EntityTrait, IntoActiveModel, QueryFilter, QueryOrder,
+};
+use serde::Deserialize;
+
+use crate::AppState;
+use crate::handlers::maintenance::service_holds;
+use crate::handlers::uploads::save_photos;
+use crate::pdf::{self, Document};
+
+#[derive(Deserialize)]
+pub(crate) struct WitnessParameters {
+    name: String,
+    capid: Option<i32>,
+    phone: Option<String>,
+    statement: Option<String>,
+}
+
+impl WitnessParameters {
+    fn into_active_model(self, incident_id: i32) -> entity::vehicle_incident_witness::ActiveModel {
+        entity::vehicle_incident_witness::ActiveModel {
+            id: NotSet,
+            incident_id: Set(incident_id),
+            name: Set(self.name.trim().to_string()),
+            capid: Set(self.capid),
+            phone: Set(self.phone),
+            statement: Set(self.statement),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct NewIncidentParameters {
+    vehicle_id: i32,
+    reported_by: i32,
+    occurred_at: NaiveDateTime,
+    location: String,
+    damage: String,
+    narrative: String,
+    #[serde(default)]
+    witnesses: Vec<WitnessParameters>,
+}
+
+pub(crate) async fn get_all_incidents(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<entity::vehicle_incident::ModelEx>>, StatusCode> {
+    let mut query = VehicleIncident::load()
+        .with(Vehicle)
+        .with(Attendee)
+        .order_by_desc(entity::vehicle_incident::Column::OccurredAt);
+
+    if let Some(s) = params.get("vehicleId") {
+        let id: i32 = s.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+        query = query.filter(entity::vehicle_incident::Column::VehicleId.eq(id));
+    }
+
+    match query.all(&state.db).await {
+        Ok(incidents) => Ok(Json(incidents)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Files the report against whoever had the vehicle and the trip it was on
+/// when the incident happened, and pulls the vehicle from service until the
+/// incident is cleared.
+pub(crate) async fn create_incident(
+    State(state): State<Arc<AppState>>,
+    Json(data): Json<NewIncidentParameters>,
+) -> Result<Json<entity::vehicle_incident::Model>, StatusCode> {
+    if data.narrative.trim().is_empty() || data.witnesses.iter().any(|w| w.name.trim().is_empty()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let vehicle = match Vehicle::find_by_id(data.vehicle_id).one(&state.db).await {
+        Ok(Some(vehicle)) => vehicle,
+        Ok(None) => return Err(StatusCode::BAD_REQUEST),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let trip = VehicleTrip::find()
+        .filter(entity::vehicle_trip::Column::VehicleId.eq(vehicle.id))
+        .filter(entity::vehicle_trip::Column::DepartedAt.lte(data.occurred_at))
+        .filter(
+            Condition::any()
+                .add(entity::vehicle_trip::Column::ReturnedAt.is_null())
+                .add(entity::vehicle_trip::Column::ReturnedAt.gte(data.occurred_at)),
+        )
+        .order_by_desc(entity::vehicle_trip::Column::DepartedAt)
+        .one(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let driver_capid = match &trip {
+        Some(trip) => Some(trip.driver_capid),
+        None => vehicle.issued_to,
+    };
+
+    let model = entity::vehicle_incident::ActiveModel {
+        id: NotSet,
+        vehicle_id: Set(vehicle.id),
+        driver_capid: Set(driver_capid),
+        trip_id: Set(trip.map(|t| t.id)),
+        occurred_at: Set(data.occurred_at),
+        location: Set(data.location),
+        damage: Set(data.damage),
+        narrative: Set(data.narrative),
+        reported_by: Set(data.reported_by),
+        reported_at: Set(Local::now().naive_local()),
+        cleared_by: Set(None),
+        cleared_at: Set(None),
+        clearance: Set(None),
+    };
+
+    let incident = match model.insert(&state.db).await {
+        Ok(incident) => incident,
+        Err(_) => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    if !data.witnesses.is_empty() {
+        VehicleIncidentWitness::insert_many(
+            data.witnesses
+                .into_iter()
+                .map(|w| w.into_active_model(incident.id)),
+        )
+        .exec(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    }
+
+    Vehicle::update_many()
+        .col_expr(entity::vehicle::Column::InService, Expr::value(false))
+        .filter(entity::vehicle::Column::Id.eq(vehicle.id))
+        .exec(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(incident))
+}
+
+pub(crate) async fn get_incident(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<entity::vehicle_incident::ModelEx>, StatusCode> {
+    match VehicleIncident::load()
+        .filter_by_id(id)
+        .with(Vehicle)
+        .with(Attendee)
+        .with(VehicleTrip)
+        .with(VehicleIncidentWitness)
+        .with(VehicleIncidentPhoto)
+        .one(&state.db)
+        .await
+    {
+        Ok(Some(incident)) => Ok(Json(incident)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn add_incident_witness(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<WitnessParameters>,
+) -> impl IntoResponse {
+    if data.name.trim().is_empty() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    match data.into_active_model(id).insert(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::BAD_REQUEST,
+    }
+}
+
+pub(crate) async fn upload_incident_photos(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    match VehicleIncident::find_by_id(id).one(&state.db).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    let photos = match save_photos(&mut multipart, &format!("incident_{id}")).await {
+        Ok(photos) => photos,
+        Err(status) => return status,
+    };
+
+    let photos = photos
+        .into_iter()
+        .map(|photo| entity::vehicle_incident_photo::ActiveModel {
+            id: NotSet,
+            incident_id: Set(id),
+            file_name: Set(photo.file_name),
+            content_type: Set(photo.content_type),
+            uploaded_at: Set(Local::now().naive_local()),
+        });
+
+    match VehicleIncidentPhoto::insert_many(photos)
+        .exec(&state.db)
+        .await
+    {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ClearIncidentParameters {
+    capid: i32,
+    clearance: String,
+}
+
+/// Signs the vehicle off after an incident. It goes back into service only
+/// if nothing else, like a grounding deficiency, is still holding it out.
+pub(crate) async fn clear_incident(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<ClearIncidentParameters>,
+) -> impl IntoResponse {
+    if data.clearance.trim().is_empty() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let incident = match VehicleIncident::find_by_id(id).one(&state.db).await {
+        Ok(Some(incident)) => incident,
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    if incident.cleared_at.is_some() {
+        return StatusCode::CONFLICT;
+    }
+
+    let vehicle_id = incident.vehicle_id;
+    let mut model = incident.into_active_model();
+
+    model.cleared_by = Set(Some(data.capid));
+    model.cleared_at = Set(Some(Local::now().naive_local()));
+    model.clearance = Set(Some(data.clearance));
+
+    match model.update(&state.db).await {
+        Ok(_) => {}
+        Err(DbErr::Query(_)) => return StatusCode::BAD_REQUEST,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    match service_holds(&state.db, vehicle_id).await {
+        Ok(holds) if holds.is_empty() => {}
+        Ok(_) => return StatusCode::OK,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    match Vehicle::update_many()
+        .col_expr(entity::vehicle::Column::InService, Expr::value(true))
+        .filter(entity::vehicle::Column::Id.eq(vehicle_id))
+        .exec(&state.db)
+        .await
+    {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Only cleared incidents can be deleted, so a report can't be dropped while
+/// it's the reason a vehicle is grounded.
+pub(crate) async fn delete_incident(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    match VehicleIncident::find_by_id(id).one(&state.db).await {
+        Ok(Some(incident)) if incident.cleared_at.is_none() => return StatusCode::CONFLICT,
+        Ok(Some(_)) => {}
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    match VehicleIncident::delete_by_id(id).exec(&state.db).await {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn get_incident_pdf(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let incident = match VehicleIncident::load()
+        .filter_by_id(id)
+        .with((Vehicle, VehicleType))
+        .with(Attendee)
+        .with(VehicleTrip)
+        .with(VehicleIncidentWitness)
+        .with(VehicleIncidentPhoto)
+        .one(&state.db)
+        .await
+    {
+        Ok(Some(incident)) => incident,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let reporter = Attendee::find_by_id(incident.reported_by)
+        .one(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let describe = |a: &entity::attendee::Model| {
+        format!(
+            "{} {} {} (CAPID {})",
+            a.rank, a.first_name, a.last_name, a.capid
+        )
+    };
+
+    let mut document = Document::new("Vehicle Incident Report");
+    document.field("Report number", &incident.id.to_string());
+    document.field(
+        "Occurred",
+        &incident.occurred_at.format("%d %b %Y %H:%M").to_string(),
+    );
+    document.field("Location", &incident.location);
+    document.field(
+        "Reported",
+        &incident.reported_at.format("%d %b %Y %H:%M").to_string(),
+    );
+    document.field(
+        "Reported by",
+        &reporter
+            .as_ref()
+            .map(describe)
+            .unwrap_or_else(|| incident.reported_by.to_string()),
+    );
+
+    document.heading("Vehicle");
+    match incident.vehicle.as_ref() {
+        Some(vehicle) => {
+            let description = match vehicle.vehicle_type.as_ref() {
+                Some(t) => format!(
+                    "Vehicle {}, {} {} {}",
+                    vehicle.id, vehicle.year, t.make, t.model
+                ),
+                None => format!("Vehicle {}", vehicle.id),
+            };
+            document.field("Description", &description);
+            document.field(
+                "Property tag",
+                vehicle.property_tag_number.as_deref().unwrap_or_default(),
+            );
+            document.field("Owned by unit", &vehicle.owned_by_unit);
+        }
+        None => document.field("Description", &format!("Vehicle {}", incident.vehicle_id)),
+    }
+
+    document.heading("Driver");
+    match incident.driver.as_ref() {
+        Some(driver) => {
+            document.field(
+                "Name",
+                &format!(
+                    "{} {} {} (CAPID {})",
+                    driver.rank, driver.first_name, driver.last_name, driver.capid
+                ),
+            );
+            document.field("Unit", &driver.unit);
+            document.field(
+                "Driver's license expires",
+                &driver
+                    .dl_expiration
+                    .map(|d| d.format("%d %b %Y").to_string())
+                    .unwrap_or_else(|| "Not on file".to_string()),
+            );
+            document.field(
+                "CAP license expires",
+                &driver
+                    .cap_license_expiration
+                    .map(|d| d.format("%d %b %Y").to_string())
+                    .unwrap_or_else(|| "Not on file".to_string()),
+            );
+        }
+        None => document.paragraph("No driver had the vehicle issued at the time."),
+    }
+
+    if let Some(trip) = incident.trip.as_ref() {
+        document.heading("Trip");
+        document.field("Purpose", &trip.purpose);
+        document.field("Destination", &trip.destination);
+        document.field(
+            "Departed",
+            &trip.departed_at.format("%d %b %Y %H:%M").to_string(),
+        );
+        document.field("Start odometer", &trip.start_odometer.to_string());
+    }
+
+    document.heading("Damage");
+    document.paragraph(&incident.damage);
+
+    document.heading("Narrative");
+    document.paragraph(&incident.narrative);
+
+    document.heading("Witnesses");
+    if incident.witnesses.is_empty() {
+        document.paragraph("No witnesses recorded.");
+    } else {
+        let rows: Vec<Vec<String>> = incident
+            .witnesses
+            .iter()
+            .map(|w| {
+                vec![
+                    w.name.clone(),
+                    w.capid.map(|c| c.to_string()).unwrap_or_default(),
+                    w.phone.clone().unwrap_or_default(),
+                    w.statement.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+        document.table(
+            &[
+                ("Name", 0.25),
+                ("CAPID", 0.12),
+                ("Phone", 0.18),
+                ("Statement", 0.45),
+            ],
+            &rows,
+        );
+    }
+
+    if !incident.photos.is_empty() {
+        document.heading("Photos");
+        for photo in incident.photos.iter() {
+            document.field("File", &photo.file_name);
+
+            // JPEGs are listed for the safety officer to pull from uploads
+            if photo.content_type != "image/png" {
+                continue;
+            }
+
+            let mut path = PathBuf::from(dotenvy::var("UPLOADS_PATH").unwrap());
+            path.push(&photo.file_name);
+
+            if let Ok(png) = tokio::fs::read(path).await {
+                let _ = document.image(&png, 300.0, 220.0);
+            }
+        }
+    }
+
+    document.heading("Certification");
+    document.signature_line("Signature of reporting member and date");
+    document.signature_line("Wing safety officer review, signature and date");
+
+    Ok(pdf::response(
+        &format!("vehicle-incident-{}.pdf", incident.id),
+        document.finish(),
+    ))
+}
@@ -0,0 +1,881 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use chrono::Local;
+use entity::prelude::{
+    Attendee, InspectionTemplate, InspectionTemplateItem, Vehicle, VehicleInspection,
+    VehicleInspectionResult, VehicleType,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, ConnectionTrait, DbConn, DbErr,
+    EntityLoaderTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder, QuerySelect, SqlErr,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::AppState;
+use crate::handlers::attendees::{COMMANDER, INCIDENT_COMMANDER, has_role};
+use crate::handlers::maintenance::{
+    GROUNDING, MINOR, is_severity, open_deficiencies, open_vehicle_deficiencies,
+};
+use crate::pdf::{self, Document};
+
+const PASS_FAIL: &str = "pass_fail";
+const NUMBER: &str = "number";
+
+// Paperwork that has to be in the vehicle before it can go out
+const DOCUMENTS: &[&str] = &[
+    "registration_present",
+    "registration_current",
+    "insurance_present",
+    "insurance_current",
+    "capf132_present",
+    "capf132_current",
+    "capf132_signed",
+];
+
+// Each tire reading and the recommended pressure it's checked against
+const PRESSURES: &[(&str, &str)] = &[
+    ("left_front_pressure", "front_recommended_pressure"),
+    ("right_front_pressure", "front_recommended_pressure"),
+    ("left_rear_pressure", "rear_recommended_pressure"),
+    ("right_rear_pressure", "rear_recommended_pressure"),
+];
+
+async fn current_template(
+    db: &DbConn,
+) -> Result<Option<entity::inspection_template::Model>, DbErr> {
+    InspectionTemplate::find()
+        .order_by_desc(entity::inspection_template::Column::Version)
+        .one(db)
+        .await
+}
+
+async fn template_items<C: ConnectionTrait>(
+    db: &C,
+    template_id: Option<i32>,
+) -> Result<Vec<entity::inspection_template_item::Model>, DbErr> {
+    let template_id = match template_id {
+        Some(id) => id,
+        None => return Ok(vec![]),
+    };
+
+    InspectionTemplateItem::find()
+        .filter(entity::inspection_template_item::Column::TemplateId.eq(template_id))
+        .order_by_asc(entity::inspection_template_item::Column::Position)
+        .all(db)
+        .await
+}
+
+#[derive(Serialize)]
+pub(crate) struct ChecklistItem {
+    pub(crate) key: String,
+    section: String,
+    pub(crate) label: String,
+    result_type: String,
+    remarks_required: bool,
+    pub(crate) severity: String,
+    pub(crate) passed: Option<bool>,
+    value: Option<i32>,
+    pub(crate) remarks: Option<String>,
+}
+
+fn checklist(
+    items: Vec<entity::inspection_template_item::Model>,
+    results: Vec<entity::vehicle_inspection_result::Model>,
+) -> Vec<ChecklistItem> {
+    let mut results: HashMap<String, entity::vehicle_inspection_result::Model> = results
+        .into_iter()
+        .map(|r| (r.item_key.clone(), r))
+        .collect();
+
+    items
+        .into_iter()
+        .map(|item| {
+            let result = results.remove(&item.key);
+
+            ChecklistItem {
+                passed: result.as_ref().and_then(|r| r.passed),
+                value: result.as_ref().and_then(|r| r.value),
+                remarks: result.and_then(|r| r.remarks),
+                key: item.key,
+                section: item.section,
+                label: item.label,
+                result_type: item.result_type,
+                remarks_required: item.remarks_required,
+                severity: item.severity,
+            }
+        })
+        .collect()
+}
+
+async fn load_checklist<C: ConnectionTrait>(
+    db: &C,
+    inspection: &entity::vehicle_inspection::Model,
+) -> Result<Vec<ChecklistItem>, DbErr> {
+    let items = template_items(db, inspection.template_id).await?;
+    let results = VehicleInspectionResult::find()
+        .filter(entity::vehicle_inspection_result::Column::InspectionId.eq(inspection.id))
+        .all(db)
+        .await?;
+
+    Ok(checklist(items, results))
+}
+
+/// The loader can't tell the inspector and incident commander apart since
+/// both are attendees, so they're looked up by CAPID instead.
+async fn load_signers(
+    db: &DbConn,
+    inspection: &mut entity::vehicle_inspection::ModelEx,
+) -> Result<(), DbErr> {
+    if let Some(capid) = inspection.inspector_capid {
+        inspection.inspector = Attendee::find_by_id(capid)
+            .one(db)
+            .await?
+            .map(Into::into)
+            .map(Box::new)
+            .into();
+    }
+
+    if let Some(capid) = inspection.ic_capid {
+        inspection.incident_commander = Attendee::find_by_id(capid)
+            .one(db)
+            .await?
+            .map(Into::into)
+            .map(Box::new)
+            .into();
+    }
+
+    Ok(())
+}
+
+/// Everything that keeps the vehicle from being mission ready. The checks
+/// only apply to the items the inspection's template actually has.
+fn readiness(checklist: &[ChecklistItem], pressure_tolerance: i32) -> Vec<String> {
+    let item = |key: &str| checklist.iter().find(|item| item.key == key);
+    let mut reasons = vec![];
+
+    for failed in checklist
+        .iter()
+        .filter(|item| item.severity == GROUNDING && item.passed == Some(false))
+    {
+        reasons.push(format!("Critical failure: {}", failed.label));
+    }
+
+    for document in DOCUMENTS.iter().filter_map(|key| item(key)) {
+        match document.passed {
+            Some(true) => {}
+            Some(false) => reasons.push(format!("Document missing: {}", document.label)),
+            None => reasons.push(format!("Document not checked: {}", document.label)),
+        }
+    }
+
+    for (tire, recommended) in PRESSURES {
+        let (Some(tire), Some(recommended)) = (item(tire), item(recommended)) else {
+            continue;
+        };
+
+        match (tire.value, recommended.value) {
+            (_, None) => {
+                let reason = format!("{} not recorded", recommended.label);
+                if !reasons.contains(&reason) {
+                    reasons.push(reason);
+                }
+            }
+            (None, _) => reasons.push(format!("{} not recorded", tire.label)),
+            (Some(actual), Some(target)) if (actual - target).abs() > pressure_tolerance => reasons
+                .push(format!(
+                    "{} of {actual} psi is outside {target} psi +/- {pressure_tolerance}",
+                    tire.label
+                )),
+            _ => {}
+        }
+    }
+
+    reasons
+}
+
+/// The checklist flattened into the `key` / `key_remarks` fields the
+/// inspection form has always used
+fn answers(checklist: &[ChecklistItem]) -> BTreeMap<String, Value> {
+    let mut answers = BTreeMap::new();
+
+    for item in checklist {
+        if item.result_type == NUMBER {
+            answers.insert(item.key.clone(), item.value.into());
+        } else {
+            answers.insert(item.key.clone(), item.passed.into());
+            answers.insert(format!("{}_remarks", item.key), item.remarks.clone().into());
+        }
+    }
+
+    answers
+}
+
+#[derive(Serialize)]
+pub(crate) struct InspectionSummary {
+    #[serde(flatten)]
+    inspection: entity::vehicle_inspection::Model,
+    #[serde(flatten)]
+    answers: BTreeMap<String, Value>,
+}
+
+pub(crate) async fn get_all_inspections(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let inspections = VehicleInspection::find()
+        .all(&state.db)
+        .await
+        .expect("Could not get inspections");
+
+    let mut items: HashMap<i32, Vec<entity::inspection_template_item::Model>> = HashMap::new();
+    for item in InspectionTemplateItem::find()
+        .order_by_asc(entity::inspection_template_item::Column::Position)
+        .all(&state.db)
+        .await
+        .expect("Could not get inspection templates")
+    {
+        items.entry(item.template_id).or_default().push(item);
+    }
+
+    let mut results: HashMap<i32, Vec<entity::vehicle_inspection_result::Model>> = HashMap::new();
+    for result in VehicleInspectionResult::find()
+        .all(&state.db)
+        .await
+        .expect("Could not get inspection results")
+    {
+        results
+            .entry(result.inspection_id)
+            .or_default()
+            .push(result);
+    }
+
+    let inspections: Vec<InspectionSummary> = inspections
+        .into_iter()
+        .map(|inspection| {
+            let items = inspection
+                .template_id
+                .and_then(|id| items.get(&id))
+                .cloned()
+                .unwrap_or_default();
+            let results = results.remove(&inspection.id).unwrap_or_default();
+
+            InspectionSummary {
+                answers: answers(&checklist(items, results)),
+                inspection,
+            }
+        })
+        .collect();
+
+    Json(inspections)
+}
+
+#[derive(Deserialize)]
+pub(crate) struct StartInspectionParameters {
+    vehicle_id: i32,
+    capid: i32,
+}
+
+pub(crate) async fn start_inspection(
+    State(state): State<Arc<AppState>>,
+    Json(data): Json<StartInspectionParameters>,
+) -> impl IntoResponse {
+    let template = match current_template(&state.db).await {
+        Ok(template) => template,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    let active_model = entity::vehicle_inspection::ActiveModel {
+        id: NotSet,
+        started_at: Set(Local::now().naive_local()),
+        vehicle_id: Set(data.vehicle_id),
+        template_id: Set(template.as_ref().map(|t| t.id)),
+        inspector_capid: Set(Some(data.capid)),
+        ..Default::default()
+    };
+
+    let inspection = match active_model.insert(&state.db).await {
+        Ok(inspection) => inspection,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    // Anything still broken from an earlier inspection starts out failed so
+    // the inspector has to look at it again
+    let items = match template_items(&state.db, inspection.template_id).await {
+        Ok(items) => items,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    let deficiencies = match open_deficiencies(&state.db, inspection.vehicle_id).await {
+        Ok(deficiencies) => deficiencies,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    let mut results = HashMap::new();
+    for deficiency in deficiencies {
+        if items
+            .iter()
+            .any(|i| i.key == deficiency.item_key && i.result_type == PASS_FAIL)
+        {
+            results.entry(deficiency.item_key.clone()).or_insert(
+                entity::vehicle_inspection_result::ActiveModel {
+                    id: NotSet,
+                    inspection_id: Set(inspection.id),
+                    item_key: Set(deficiency.item_key),
+                    passed: Set(Some(false)),
+                    value: Set(None),
+                    remarks: Set(Some(deficiency.description)),
+                },
+            );
+        }
+    }
+
+    if results.is_empty() {
+        return StatusCode::OK;
+    }
+
+    match VehicleInspectionResult::insert_many(results.into_values())
+        .exec(&state.db)
+        .await
+    {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct Inspection {
+    #[serde(flatten)]
+    inspection: entity::vehicle_inspection::ModelEx,
+    #[serde(flatten)]
+    answers: BTreeMap<String, Value>,
+    checklist: Vec<ChecklistItem>,
+    mission_ready_reasons: Vec<String>,
+}
+
+pub(crate) async fn get_inspection(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<Inspection>, StatusCode> {
+    let mut inspection = match VehicleInspection::load()
+        .filter_by_id(id)
+        .with(Vehicle)
+        .with(VehicleInspectionResult)
+        .one(&state.db)
+        .await
+    {
+        Ok(Some(inspection)) => inspection,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    load_signers(&state.db, &mut inspection)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let items = template_items(&state.db, inspection.template_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let results = inspection.results.iter().cloned().map(Into::into).collect();
+    let checklist = checklist(items, results);
+
+    Ok(Json(Inspection {
+        answers: answers(&checklist),
+        mission_ready_reasons: readiness(&checklist, state.config.inspection.pressure_tolerance),
+        inspection,
+        checklist,
+    }))
+}
+
+pub(crate) async fn get_inspection_pdf(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let mut inspection = match VehicleInspection::load()
+        .filter_by_id(id)
+        .with((Vehicle, VehicleType))
+        .one(&state.db)
+        .await
+    {
+        Ok(Some(inspection)) => inspection,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    load_signers(&state.db, &mut inspection)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let checklist = load_checklist(&state.db, &inspection.clone().into())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let describe = |a: &entity::attendee::ModelEx| {
+        format!(
+            "{} {} {} (CAPID {})",
+            a.rank, a.first_name, a.last_name, a.capid
+        )
+    };
+
+    let mut document = Document::new("CAP Vehicle Inspection Checklist");
+    document.field("Inspection number", &inspection.id.to_string());
+    document.field(
+        "Started",
+        &inspection.started_at.format("%d %b %Y %H:%M").to_string(),
+    );
+
+    document.heading("Vehicle");
+    match inspection.vehicle.as_ref() {
+        Some(vehicle) => {
+            let description = match vehicle.vehicle_type.as_ref() {
+                Some(t) => format!(
+                    "Vehicle {}, {} {} {}",
+                    vehicle.id, vehicle.year, t.make, t.model
+                ),
+                None => format!("Vehicle {}", vehicle.id),
+            };
+            document.field("Description", &description);
+            document.field(
+                "Property tag",
+                vehicle.property_tag_number.as_deref().unwrap_or_default(),
+            );
+            document.field("Owned by unit", &vehicle.owned_by_unit);
+        }
+        None => document.field("Description", &format!("Vehicle {}", inspection.vehicle_id)),
+    }
+
+    let mut sections: Vec<(&str, Vec<&ChecklistItem>)> = vec![];
+    for item in &checklist {
+        match sections.iter_mut().find(|(name, _)| *name == item.section) {
+            Some((_, items)) => items.push(item),
+            None => sections.push((&item.section, vec![item])),
+        }
+    }
+
+    for (section, items) in sections {
+        document.heading(section);
+
+        let rows: Vec<Vec<String>> = items
+            .iter()
+            .filter(|item| item.result_type == PASS_FAIL)
+            .map(|item| {
+                vec![
+                    item.label.clone(),
+                    match item.passed {
+                        Some(true) => "Pass".to_string(),
+                        Some(false) => "Fail".to_string(),
+                        None => "Not checked".to_string(),
+                    },
+                    item.remarks.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+        if !rows.is_empty() {
+            document.table(&[("Item", 0.4), ("Result", 0.15), ("Remarks", 0.45)], &rows);
+        }
+
+        // Readings such as tire pressures and the last oil change mileage
+        for item in items.iter().filter(|item| item.result_type == NUMBER) {
+            document.field(
+                &item.label,
+                &item
+                    .value
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "Not recorded".to_string()),
+            );
+        }
+    }
+
+    document.heading("Inspector");
+    document.field(
+        "Mission ready",
+        match inspection.is_mission_ready {
+            Some(true) => "Yes",
+            Some(false) => "No",
+            None => "Not determined",
+        },
+    );
+    let caption = match (inspection.inspector.as_ref(), inspection.signed_at) {
+        (Some(inspector), Some(at)) => format!(
+            "/s/ {}, {}",
+            describe(inspector),
+            at.format("%d %b %Y %H:%M")
+        ),
+        (None, Some(at)) => format!("Signed {}", at.format("%d %b %Y %H:%M")),
+        _ => "Signature of inspector and date".to_string(),
+    };
+    document.signature_line(&caption);
+
+    document.heading("Incident Commander Override");
+    document.paragraph(
+        "The incident commander may authorize use of a vehicle that is not mission ready \
+         after reviewing the deficiencies noted above and accepting the associated risk.",
+    );
+    if let Some(justification) = &inspection.ic_justification {
+        document.field("Justification", justification);
+    }
+    let caption = match (
+        inspection.incident_commander.as_ref(),
+        inspection.ic_signed_at,
+    ) {
+        (Some(commander), Some(at)) => format!(
+            "/s/ {}, {}",
+            describe(commander),
+            at.format("%d %b %Y %H:%M")
+        ),
+        (None, Some(at)) => format!("Signed {}", at.format("%d %b %Y %H:%M")),
+        _ => "Signature of incident commander and date".to_string(),
+    };
+    document.signature_line(&caption);
+
+    Ok(pdf::response(
+        &format!("vehicle-inspection-{}.pdf", inspection.id),
+        document.finish(),
+    ))
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UpdateInspectionParameters {
+    id: i32,
+    inspector_capid: Option<i32>,
+    #[serde(flatten)]
+    answers: HashMap<String, Value>,
+}
+
+pub(crate) async fn update_inspection(
+    State(state): State<Arc<AppState>>,
+    Json(data): Json<UpdateInspectionParameters>,
+) -> impl IntoResponse {
+    let inspection = match VehicleInspection::find_by_id(data.id).one(&state.db).await {
+        Ok(Some(inspection)) => inspection,
+        Ok(None) => return StatusCode::BAD_REQUEST,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    // Once signed, the results are what the inspector and any IC override
+    // vouched for
+    if inspection.signed_at.is_some() {
+        return StatusCode::CONFLICT;
+    }
+
+    let items = match template_items(&state.db, inspection.template_id).await {
+        Ok(items) => items,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    let mut results = vec![];
+    for item in &items {
+        let answer = data.answers.get(&item.key).unwrap_or(&Value::Null);
+        let remarks = data
+            .answers
+            .get(&format!("{}_remarks", item.key))
+            .unwrap_or(&Value::Null);
+
+        let (passed, value) = match (item.result_type.as_str(), answer) {
+            (_, Value::Null) => (None, None),
+            (NUMBER, Value::Number(n)) => match n.as_i64().and_then(|n| i32::try_from(n).ok()) {
+                Some(n) => (None, Some(n)),
+                None => return StatusCode::BAD_REQUEST,
+            },
+            (PASS_FAIL, Value::Bool(b)) => (Some(*b), None),
+            _ => return StatusCode::BAD_REQUEST,
+        };
+
+        let remarks = match remarks {
+            Value::Null => None,
+            Value::String(s) if s.trim().is_empty() => None,
+            Value::String(s) => Some(s.clone()),
+            _ => return StatusCode::BAD_REQUEST,
+        };
+
+        if passed.is_none() && value.is_none() && remarks.is_none() {
+            continue;
+        }
+
+        results.push(entity::vehicle_inspection_result::Model {
+            id: 0,
+            inspection_id: inspection.id,
+            item_key: item.key.clone(),
+            passed,
+            value,
+            remarks,
+        });
+    }
+
+    let ready = readiness(
+        &checklist(items, results.clone()),
+        state.config.inspection.pressure_tolerance,
+    )
+    .is_empty();
+
+    let txn = match state.db.begin().await {
+        Ok(txn) => txn,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    let mut model = inspection.into_active_model();
+    model.is_mission_ready = Set(Some(ready));
+    if data.inspector_capid.is_some() {
+        model.inspector_capid = Set(data.inspector_capid);
+    }
+
+    if model.update(&txn).await.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    if VehicleInspectionResult::delete_many()
+        .filter(entity::vehicle_inspection_result::Column::InspectionId.eq(data.id))
+        .exec(&txn)
+        .await
+        .is_err()
+    {
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    let results = results
+        .into_iter()
+        .map(|r| entity::vehicle_inspection_result::ActiveModel {
+            id: NotSet,
+            inspection_id: Set(r.inspection_id),
+            item_key: Set(r.item_key),
+            passed: Set(r.passed),
+            value: Set(r.value),
+            remarks: Set(r.remarks),
+        })
+        .collect::<Vec<_>>();
+
+    if !results.is_empty()
+        && VehicleInspectionResult::insert_many(results)
+            .exec(&txn)
+            .await
+            .is_err()
+    {
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    match txn.commit().await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SignInspectionParameters {
+    capid: i32,
+}
+
+pub(crate) async fn sign_inspection(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<SignInspectionParameters>,
+) -> impl IntoResponse {
+    let txn = match state.db.begin().await {
+        Ok(txn) => txn,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    let inspection = match VehicleInspection::find_by_id(id)
+        .lock_exclusive()
+        .one(&txn)
+        .await
+    {
+        Ok(Some(inspection)) => inspection,
+        Ok(None) => return StatusCode::BAD_REQUEST,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    // Signing again would restart the validity window without a new inspection
+    if inspection.signed_at.is_some() {
+        return StatusCode::CONFLICT;
+    }
+
+    let checklist = match load_checklist(&txn, &inspection).await {
+        Ok(checklist) => checklist,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    // A failed item has to say what's wrong with it before it can be signed off
+    if checklist
+        .iter()
+        .any(|item| item.remarks_required && item.passed == Some(false) && item.remarks.is_none())
+    {
+        return StatusCode::UNPROCESSABLE_ENTITY;
+    }
+
+    let mut model = inspection.into_active_model();
+    model.inspector_capid = Set(Some(data.capid));
+    model.signed_at = Set(Some(Local::now().naive_local()));
+    model.is_mission_ready = Set(Some(
+        readiness(&checklist, state.config.inspection.pressure_tolerance).is_empty(),
+    ));
+
+    let inspection = match model.update(&txn).await {
+        Ok(inspection) => inspection,
+        Err(DbErr::RecordNotUpdated) => return StatusCode::BAD_REQUEST,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    if open_vehicle_deficiencies(&txn, &inspection, &checklist)
+        .await
+        .is_err()
+    {
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    match txn.commit().await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct OverrideInspectionParameters {
+    capid: i32,
+    justification: String,
+}
+
+pub(crate) async fn override_inspection(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<OverrideInspectionParameters>,
+) -> impl IntoResponse {
+    if data.justification.trim().is_empty() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let inspection = match VehicleInspection::find_by_id(id).one(&state.db).await {
+        Ok(Some(inspection)) => inspection,
+        Ok(None) => return StatusCode::BAD_REQUEST,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    // The IC accepts the risk of a finished inspection, not a half-done one
+    if inspection.signed_at.is_none() {
+        return StatusCode::CONFLICT;
+    }
+
+    match has_role(&state.db, data.capid, &[INCIDENT_COMMANDER, COMMANDER]).await {
+        Ok(true) => {}
+        Ok(false) => return StatusCode::FORBIDDEN,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    let mut model = inspection.into_active_model();
+    model.ic_capid = Set(Some(data.capid));
+    model.ic_signed_at = Set(Some(Local::now().naive_local()));
+    model.ic_justification = Set(Some(data.justification));
+
+    match model.update(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(DbErr::RecordNotUpdated) => StatusCode::BAD_REQUEST,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn delete_inspection(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    match VehicleInspection::delete_by_id(id).exec(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(DbErr::RecordNotFound(_)) => StatusCode::NOT_FOUND,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn get_all_inspection_templates(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let templates = InspectionTemplate::load()
+        .with(InspectionTemplateItem)
+        .order_by_desc(entity::inspection_template::Column::Version)
+        .all(&state.db)
+        .await
+        .expect("Could not get inspection templates");
+
+    Json(templates)
+}
+
+#[derive(Deserialize)]
+pub(crate) struct TemplateItemParameters {
+    key: String,
+    section: String,
+    label: String,
+    result_type: String,
+    remarks_required: bool,
+    severity: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CreateTemplateParameters {
+    items: Vec<TemplateItemParameters>,
+}
+
+/// Publishes a new version of the checklist. Inspections already started
+/// keep the version they were started against.
+pub(crate) async fn create_inspection_template(
+    State(state): State<Arc<AppState>>,
+    Json(data): Json<CreateTemplateParameters>,
+) -> impl IntoResponse {
+    if data.items.is_empty()
+        || data.items.iter().any(|item| {
+            item.key.trim().is_empty()
+                || (item.result_type != PASS_FAIL && item.result_type != NUMBER)
+                || item.severity.as_deref().is_some_and(|s| !is_severity(s))
+        })
+    {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let version = match current_template(&state.db).await {
+        Ok(template) => template.map_or(1, |t| t.version + 1),
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    let txn = match state.db.begin().await {
+        Ok(txn) => txn,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    let template = match (entity::inspection_template::ActiveModel {
+        id: NotSet,
+        version: Set(version),
+        created_at: Set(Local::now().naive_local()),
+    })
+    .insert(&txn)
+    .await
+    {
+        Ok(template) => template,
+        Err(e) => match e.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => return StatusCode::CONFLICT,
+            _ => return StatusCode::INTERNAL_SERVER_ERROR,
+        },
+    };
+
+    let items = data.items.into_iter().enumerate().map(|(position, item)| {
+        entity::inspection_template_item::ActiveModel {
+            id: NotSet,
+            template_id: Set(template.id),
+            position: Set(position as i32),
+            key: Set(item.key.trim().to_string()),
+            section: Set(item.section),
+            label: Set(item.label),
+            result_type: Set(item.result_type),
+            remarks_required: Set(item.remarks_required),
+            severity: Set(item.severity.unwrap_or_else(|| MINOR.to_string())),
+        }
+    });
+
+    if let Err(e) = InspectionTemplateItem::insert_many(items).exec(&txn).await {
+        return match e.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+    }
+
+    match txn.commit().await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
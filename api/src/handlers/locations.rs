@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use entity::prelude::Location;
+use sea_orm::{ActiveModelTrait, ActiveValue::*, DbErr, EntityTrait, QueryOrder, SqlErr};
+use serde::Deserialize;
+
+use crate::AppState;
+
+#[derive(Deserialize)]
+pub(crate) struct LocationParameters {
+    name: String,
+    capacity: Option<i32>,
+}
+
+fn status(e: DbErr) -> StatusCode {
+    match e.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => StatusCode::CONFLICT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn get_all_locations(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<entity::location::Model>>, StatusCode> {
+    match Location::find()
+        .order_by_asc(entity::location::Column::Name)
+        .all(&state.db)
+        .await
+    {
+        Ok(locations) => Ok(Json(locations)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn create_location(
+    State(state): State<Arc<AppState>>,
+    Json(data): Json<LocationParameters>,
+) -> Result<Json<entity::location::Model>, StatusCode> {
+    if data.name.trim().is_empty() || data.capacity.is_some_and(|c| c < 1) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let model = entity::location::ActiveModel {
+        id: NotSet,
+        name: Set(data.name.trim().to_string()),
+        capacity: Set(data.capacity),
+    };
+
+    model.insert(&state.db).await.map(Json).map_err(status)
+}
+
+pub(crate) async fn update_location(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<LocationParameters>,
+) -> impl IntoResponse {
+    if data.name.trim().is_empty() || data.capacity.is_some_and(|c| c < 1) {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let model = entity::location::ActiveModel {
+        id: Unchanged(id),
+        name: Set(data.name.trim().to_string()),
+        capacity: Set(data.capacity),
+    };
+
+    match model.update(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(DbErr::RecordNotUpdated) => StatusCode::NOT_FOUND,
+        Err(e) => status(e),
+    }
+}
+
+pub(crate) async fn delete_location(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    match Location::delete_by_id(id).exec(&state.db).await {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
@@ -0,0 +1,536 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use chrono::Local;
+use entity::prelude::{Attendee, BedAssignment, Building, Flight, FlightMember, Room};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, ConnectionTrait, DbConn, DbErr,
+    EntityLoaderTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, SqlErr, TransactionTrait,
+};
+use serde::Deserialize;
+
+use crate::AppState;
+use crate::export;
+use crate::handlers::attendees::{is_senior_member, normalize_gender};
+use crate::pdf::{self, Document};
+
+const BAY: &str = "bay";
+const ROOM: &str = "room";
+
+#[derive(Deserialize)]
+pub(crate) struct BuildingParameters {
+    name: String,
+    notes: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct RoomParameters {
+    name: String,
+    room_type: String,
+    gender: String,
+    capacity: i32,
+}
+
+impl RoomParameters {
+    fn valid(&self) -> bool {
+        !self.name.trim().is_empty()
+            && matches!(self.room_type.as_str(), BAY | ROOM)
+            && normalize_gender(&self.gender).is_some()
+            && self.capacity > 0
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct BedParameters {
+    capid: i32,
+    bed: Option<i32>,
+}
+
+fn status(e: DbErr) -> StatusCode {
+    match e.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => StatusCode::CONFLICT,
+        Some(SqlErr::ForeignKeyConstraintViolation(_)) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Every building with its rooms and who's in them.
+pub(crate) async fn get_all_buildings(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<entity::building::ModelEx>>, StatusCode> {
+    match Building::load()
+        .with((Room, BedAssignment))
+        .order_by_asc(entity::building::Column::Name)
+        .all(&state.db)
+        .await
+    {
+        Ok(buildings) => Ok(Json(buildings)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn create_building(
+    State(state): State<Arc<AppState>>,
+    Json(data): Json<BuildingParameters>,
+) -> Result<Json<entity::building::Model>, StatusCode> {
+    if data.name.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let model = entity::building::ActiveModel {
+        id: NotSet,
+        name: Set(data.name.trim().to_string()),
+        notes: Set(data.notes),
+    };
+
+    model.insert(&state.db).await.map(Json).map_err(status)
+}
+
+pub(crate) async fn update_building(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<BuildingParameters>,
+) -> impl IntoResponse {
+    if data.name.trim().is_empty() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let model = entity::building::ActiveModel {
+        id: Unchanged(id),
+        name: Set(data.name.trim().to_string()),
+        notes: Set(data.notes),
+    };
+
+    match model.update(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(DbErr::RecordNotUpdated) => StatusCode::NOT_FOUND,
+        Err(e) => status(e),
+    }
+}
+
+pub(crate) async fn delete_building(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    match Building::delete_by_id(id).exec(&state.db).await {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn create_room(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<RoomParameters>,
+) -> Result<Json<entity::room::Model>, StatusCode> {
+    if !data.valid() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let model = entity::room::ActiveModel {
+        id: NotSet,
+        building_id: Set(id),
+        name: Set(data.name.trim().to_string()),
+        room_type: Set(data.room_type),
+        gender: Set(normalize_gender(&data.gender)
+            .unwrap_or_default()
+            .to_string()),
+        capacity: Set(data.capacity),
+    };
+
+    model.insert(&state.db).await.map(Json).map_err(status)
+}
+
+pub(crate) async fn get_room(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<entity::room::ModelEx>, StatusCode> {
+    match Room::load()
+        .filter_by_id(id)
+        .with(Building)
+        .with((BedAssignment, Attendee))
+        .one(&state.db)
+        .await
+    {
+        Ok(Some(room)) => Ok(Json(room)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn occupants<C: ConnectionTrait>(
+    db: &C,
+    room_id: i32,
+) -> Result<Vec<(entity::bed_assignment::Model, entity::attendee::Model)>, DbErr> {
+    Ok(BedAssignment::find()
+        .filter(entity::bed_assignment::Column::RoomId.eq(room_id))
+        .find_also_related(Attendee)
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|(bed, attendee)| attendee.map(|a| (bed, a)))
+        .collect())
+}
+
+/// Rooms can be renamed or redesignated as long as everyone already in them
+/// still fits: no beds past the new capacity and nobody of the other gender.
+pub(crate) async fn update_room(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<RoomParameters>,
+) -> impl IntoResponse {
+    if !data.valid() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let txn = match state.db.begin().await {
+        Ok(txn) => txn,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    match Room::find_by_id(id).lock_exclusive().one(&txn).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    let occupants = match occupants(&txn, id).await {
+        Ok(occupants) => occupants,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    if occupants.iter().any(|(bed, attendee)| {
+        bed.bed > data.capacity
+            || normalize_gender(&attendee.gender) != normalize_gender(&data.gender)
+    }) {
+        return StatusCode::UNPROCESSABLE_ENTITY;
+    }
+
+    let model = entity::room::ActiveModel {
+        id: Unchanged(id),
+        building_id: NotSet,
+        name: Set(data.name.trim().to_string()),
+        room_type: Set(data.room_type),
+        gender: Set(normalize_gender(&data.gender)
+            .unwrap_or_default()
+            .to_string()),
+        capacity: Set(data.capacity),
+    };
+
+    match model.update(&txn).await {
+        Ok(_) => {}
+        Err(DbErr::RecordNotUpdated) => return StatusCode::NOT_FOUND,
+        Err(e) => return status(e),
+    }
+
+    match txn.commit().await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn delete_room(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    match Room::delete_by_id(id).exec(&state.db).await {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Puts an attendee in a bed, the lowest free one unless a bed is given.
+/// The room has to have space, match the attendee's gender, and hold only
+/// cadets or only senior members; any of those failing is a 422, as is an
+/// attendee whose gender on file isn't recognizable as male or female.
+pub(crate) async fn assign_bed(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<BedParameters>,
+) -> Result<Json<entity::bed_assignment::Model>, StatusCode> {
+    let txn = state
+        .db
+        .begin()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // Holding the room keeps two assignments from both passing the checks
+    // against the same occupants
+    let room = match Room::find_by_id(id).lock_exclusive().one(&txn).await {
+        Ok(Some(room)) => room,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let attendee = match Attendee::find_by_id(data.capid).one(&txn).await {
+        Ok(Some(attendee)) => attendee,
+        Ok(None) => return Err(StatusCode::BAD_REQUEST),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let Some(gender) = normalize_gender(&attendee.gender) else {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    };
+
+    let occupants = occupants(&txn, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if occupants.len() as i32 >= room.capacity
+        || Some(gender) != normalize_gender(&room.gender)
+        || occupants
+            .iter()
+            .any(|(_, a)| is_senior_member(a) != is_senior_member(&attendee))
+    {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    let taken = |n: i32| occupants.iter().any(|(bed, _)| bed.bed == n);
+    let bed = match data.bed {
+        Some(n) if n < 1 || n > room.capacity => return Err(StatusCode::BAD_REQUEST),
+        Some(n) if taken(n) => return Err(StatusCode::CONFLICT),
+        Some(n) => n,
+        None => match (1..=room.capacity).find(|n| !taken(*n)) {
+            Some(n) => n,
+            None => return Err(StatusCode::UNPROCESSABLE_ENTITY),
+        },
+    };
+
+    let model = entity::bed_assignment::ActiveModel {
+        id: NotSet,
+        room_id: Set(id),
+        bed: Set(bed),
+        capid: Set(attendee.capid),
+        assigned_at: Set(Local::now().naive_local()),
+    };
+
+    let assignment = model.insert(&txn).await.map_err(status)?;
+
+    match txn.commit().await {
+        Ok(_) => Ok(Json(assignment)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn unassign_bed(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let capid: i32 = match params.get("capid").map(|s| s.parse()) {
+        Some(Ok(capid)) => capid,
+        _ => return StatusCode::BAD_REQUEST,
+    };
+
+    match BedAssignment::delete_many()
+        .filter(entity::bed_assignment::Column::RoomId.eq(id))
+        .filter(entity::bed_assignment::Column::Capid.eq(capid))
+        .exec(&state.db)
+        .await
+    {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+struct RosterRoom {
+    building: String,
+    room: entity::room::Model,
+    beds: Vec<(i32, entity::attendee::Model)>,
+}
+
+/// Rooms in building and room order with their occupants by bed, narrowed by
+/// `?buildingId=` or `?roomId=`, and a flight name for everyone in one.
+async fn roster(
+    db: &DbConn,
+    params: &HashMap<String, String>,
+) -> Result<(Vec<RosterRoom>, HashMap<i32, String>), StatusCode> {
+    let mut query = Room::load()
+        .with(Building)
+        .with((BedAssignment, Attendee))
+        .order_by_asc(entity::room::Column::BuildingId)
+        .order_by_asc(entity::room::Column::Name);
+
+    if let Some(s) = params.get("buildingId") {
+        let id: i32 = s.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+        query = query.filter(entity::room::Column::BuildingId.eq(id));
+    }
+
+    if let Some(s) = params.get("roomId") {
+        let id: i32 = s.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+        query = query.filter(entity::room::Column::Id.eq(id));
+    }
+
+    let rooms = query
+        .all(db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut roster: Vec<RosterRoom> = rooms
+        .into_iter()
+        .map(|room| {
+            let building = room
+                .building
+                .as_ref()
+                .map(|b| b.name.clone())
+                .unwrap_or_default();
+
+            let mut beds: Vec<(i32, entity::attendee::Model)> = room
+                .beds
+                .iter()
+                .filter_map(|b| b.attendee.as_ref().map(|a| (b.bed, a.clone().into())))
+                .collect();
+            beds.sort_by_key(|(bed, _)| *bed);
+
+            RosterRoom {
+                building,
+                room: room.into(),
+                beds,
+            }
+        })
+        .collect();
+    roster.sort_by(|a, b| a.building.cmp(&b.building));
+
+    let flights: HashMap<i32, String> = FlightMember::find()
+        .find_also_related(Flight)
+        .all(db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .into_iter()
+        .filter_map(|(member, flight)| flight.map(|f| (member.capid, f.name)))
+        .collect();
+
+    Ok((roster, flights))
+}
+
+fn room_type(room: &entity::room::Model) -> &'static str {
+    match room.room_type.as_str() {
+        BAY => "Bay",
+        _ => "Room",
+    }
+}
+
+fn gender(room: &entity::room::Model) -> &'static str {
+    match room.gender.as_str() {
+        "F" => "Female",
+        _ => "Male",
+    }
+}
+
+/// A page section per room, with a row for every bed so the sheet can go on
+/// the door and be marked up as people move.
+pub(crate) async fn get_room_roster_pdf(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (rooms, flights) = roster(&state.db, &params).await?;
+
+    let mut document = Document::new("Room Roster");
+
+    for entry in rooms {
+        let members = match entry.beds.first() {
+            Some((_, a)) if is_senior_member(a) => "Senior members",
+            Some(_) => "Cadets",
+            None => "Empty",
+        };
+
+        document.heading(&format!("{} {}", entry.building, entry.room.name));
+        document.field(
+            "Designation",
+            &format!(
+                "{} {}, {}",
+                gender(&entry.room),
+                room_type(&entry.room).to_lowercase(),
+                members.to_lowercase()
+            ),
+        );
+        document.field(
+            "Occupancy",
+            &format!("{} of {}", entry.beds.len(), entry.room.capacity),
+        );
+
+        let rows: Vec<Vec<String>> = (1..=entry.room.capacity)
+            .map(|n| match entry.beds.iter().find(|(bed, _)| *bed == n) {
+                Some((_, a)) => vec![
+                    n.to_string(),
+                    format!("{} {}, {}", a.rank, a.last_name, a.first_name),
+                    a.capid.to_string(),
+                    flights.get(&a.capid).cloned().unwrap_or_default(),
+                    a.unit.clone(),
+                ],
+                None => vec![
+                    n.to_string(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                ],
+            })
+            .collect();
+
+        document.table(
+            &[
+                ("Bed", 0.08),
+                ("Name", 0.38),
+                ("CAPID", 0.14),
+                ("Flight", 0.2),
+                ("Unit", 0.2),
+            ],
+            &rows,
+        );
+    }
+
+    Ok(pdf::response("room-roster.pdf", document.finish()))
+}
+
+pub(crate) async fn export_room_roster(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (rooms, flights) = roster(&state.db, &params).await?;
+
+    let mut rows = vec![];
+    for entry in rooms {
+        for (bed, a) in entry.beds {
+            rows.push(vec![
+                entry.building.clone(),
+                entry.room.name.clone(),
+                room_type(&entry.room).to_string(),
+                entry.room.gender.clone(),
+                bed.to_string(),
+                a.capid.to_string(),
+                a.rank,
+                a.last_name,
+                a.first_name,
+                a.member_type,
+                flights.get(&a.capid).cloned().unwrap_or_default(),
+                a.unit,
+            ]);
+        }
+    }
+
+    export::csv(
+        "room-roster.csv",
+        &[
+            "Building",
+            "Room",
+            "Type",
+            "Gender",
+            "Bed",
+            "CAPID",
+            "Rank",
+            "Last Name",
+            "First Name",
+            "Member Type",
+            "Flight",
+            "Unit",
+        ],
+        &rows,
+    )
+}
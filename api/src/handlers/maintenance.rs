@@ -0,0 +1,567 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use chrono::Local;
+use entity::prelude::{Attendee, Radio, RadioTicket, Vehicle, VehicleDeficiency, VehicleIncident};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, ConnectionTrait, DbConn, DbErr,
+    EntityLoaderTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder,
+};
+use serde::Deserialize;
+
+use crate::AppState;
+use crate::handlers::inspections::ChecklistItem;
+
+pub(crate) async fn open_radio_ticket(
+    db: &DbConn,
+    radio_id: i32,
+    problem: String,
+    reported_by: Option<i32>,
+) -> Result<entity::radio_ticket::Model, DbErr> {
+    entity::radio_ticket::ActiveModel {
+        id: NotSet,
+        radio_id: Set(radio_id),
+        problem: Set(problem),
+        reported_by: Set(reported_by),
+        reported_at: Set(Local::now().naive_local()),
+        status: Set("open".to_string()),
+        repair_actions: Set(None),
+        returned_by: Set(None),
+        returned_at: Set(None),
+    }
+    .insert(db)
+    .await
+}
+
+/// Tickets against a radio that haven't been through the return-to-service
+/// sign-off. Any of these keeps the radio out of service.
+pub(crate) async fn open_radio_tickets(
+    db: &DbConn,
+    radio_id: i32,
+) -> Result<Vec<entity::radio_ticket::Model>, DbErr> {
+    RadioTicket::find()
+        .filter(entity::radio_ticket::Column::RadioId.eq(radio_id))
+        .filter(entity::radio_ticket::Column::Status.ne("closed"))
+        .order_by_asc(entity::radio_ticket::Column::ReportedAt)
+        .all(db)
+        .await
+}
+
+pub(crate) async fn get_all_radio_tickets(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<entity::radio_ticket::ModelEx>>, StatusCode> {
+    let mut query = RadioTicket::load()
+        .with(Radio)
+        .with(Attendee)
+        .order_by_desc(entity::radio_ticket::Column::ReportedAt);
+
+    if let Some(s) = params.get("radioId") {
+        match s.parse::<i32>() {
+            Ok(id) => query = query.filter(entity::radio_ticket::Column::RadioId.eq(id)),
+            Err(_) => return Err(StatusCode::BAD_REQUEST),
+        }
+    }
+
+    if let Some(status) = params.get("status") {
+        query = query.filter(entity::radio_ticket::Column::Status.eq(status));
+    }
+
+    match query.all(&state.db).await {
+        Ok(tickets) => Ok(Json(tickets)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct NewTicketParameters {
+    radio_id: i32,
+    problem: String,
+    reported_by: Option<i32>,
+}
+
+pub(crate) async fn create_radio_ticket(
+    State(state): State<Arc<AppState>>,
+    Json(data): Json<NewTicketParameters>,
+) -> Result<Json<entity::radio_ticket::Model>, StatusCode> {
+    if data.problem.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    match open_radio_ticket(&state.db, data.radio_id, data.problem, data.reported_by).await {
+        Ok(ticket) => Ok(Json(ticket)),
+        Err(DbErr::Query(_)) => Err(StatusCode::BAD_REQUEST),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn get_radio_ticket(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<entity::radio_ticket::ModelEx>, StatusCode> {
+    match RadioTicket::load()
+        .filter_by_id(id)
+        .with(Radio)
+        .with(Attendee)
+        .one(&state.db)
+        .await
+    {
+        Ok(Some(ticket)) => Ok(Json(ticket)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UpdateTicketParameters {
+    status: Option<String>,
+    repair_actions: Option<String>,
+}
+
+pub(crate) async fn update_radio_ticket(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<UpdateTicketParameters>,
+) -> impl IntoResponse {
+    let ticket = match RadioTicket::find_by_id(id).one(&state.db).await {
+        Ok(Some(ticket)) => ticket,
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    if ticket.status == "closed" {
+        return StatusCode::CONFLICT;
+    }
+
+    let mut model = ticket.into_active_model();
+
+    // Closing goes through the return-to-service sign-off
+    if let Some(status) = data.status {
+        if !matches!(status.as_str(), "open" | "in_repair") {
+            return StatusCode::BAD_REQUEST;
+        }
+        model.status = Set(status);
+    }
+    if data.repair_actions.is_some() {
+        model.repair_actions = Set(data.repair_actions);
+    }
+
+    match model.update(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ReturnToServiceParameters {
+    capid: i32,
+    repair_actions: Option<String>,
+}
+
+pub(crate) async fn return_radio_to_service(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<ReturnToServiceParameters>,
+) -> impl IntoResponse {
+    let ticket = match RadioTicket::find_by_id(id).one(&state.db).await {
+        Ok(Some(ticket)) => ticket,
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    if ticket.status == "closed" {
+        return StatusCode::CONFLICT;
+    }
+
+    let radio_id = ticket.radio_id;
+    let mut model = ticket.into_active_model();
+
+    model.status = Set("closed".to_string());
+    if data.repair_actions.is_some() {
+        model.repair_actions = Set(data.repair_actions);
+    }
+    model.returned_by = Set(Some(data.capid));
+    model.returned_at = Set(Some(Local::now().naive_local()));
+
+    match model.update(&state.db).await {
+        Ok(_) => {}
+        Err(DbErr::Query(_)) => return StatusCode::BAD_REQUEST,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    // The radio stays down while any other ticket against it is still open
+    match open_radio_tickets(&state.db, radio_id).await {
+        Ok(tickets) if tickets.is_empty() => {}
+        Ok(_) => return StatusCode::OK,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    let mut radio = match Radio::find_by_id(radio_id).one(&state.db).await {
+        Ok(Some(radio)) => radio.into_active_model(),
+        Ok(None) => return StatusCode::OK,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    radio.in_service = Set(true);
+
+    match radio.update(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn delete_radio_ticket(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    match RadioTicket::find_by_id(id).one(&state.db).await {
+        Ok(Some(ticket)) if ticket.status != "closed" => return StatusCode::CONFLICT,
+        Ok(Some(_)) => {}
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    match RadioTicket::delete_by_id(id).exec(&state.db).await {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) const MINOR: &str = "minor";
+pub(crate) const GROUNDING: &str = "grounding";
+const TURN_IN: &str = "turn_in";
+
+pub(crate) fn is_severity(severity: &str) -> bool {
+    matches!(severity, MINOR | "major" | GROUNDING)
+}
+
+pub(crate) async fn open_deficiencies<C: ConnectionTrait>(
+    db: &C,
+    vehicle_id: i32,
+) -> Result<Vec<entity::vehicle_deficiency::Model>, DbErr> {
+    VehicleDeficiency::find()
+        .filter(entity::vehicle_deficiency::Column::VehicleId.eq(vehicle_id))
+        .filter(entity::vehicle_deficiency::Column::ClosedAt.is_null())
+        .order_by_asc(entity::vehicle_deficiency::Column::OpenedAt)
+        .all(db)
+        .await
+}
+
+/// What's keeping a vehicle out of service, one reason per open grounding
+/// deficiency or uncleared incident. Nothing here means it can go back into
+/// service.
+pub(crate) async fn service_holds<C: ConnectionTrait>(
+    db: &C,
+    vehicle_id: i32,
+) -> Result<Vec<String>, DbErr> {
+    let deficiencies = VehicleDeficiency::find()
+        .filter(entity::vehicle_deficiency::Column::VehicleId.eq(vehicle_id))
+        .filter(entity::vehicle_deficiency::Column::Severity.eq(GROUNDING))
+        .filter(entity::vehicle_deficiency::Column::ClosedAt.is_null())
+        .order_by_asc(entity::vehicle_deficiency::Column::OpenedAt)
+        .all(db)
+        .await?;
+
+    let incidents = VehicleIncident::find()
+        .filter(entity::vehicle_incident::Column::VehicleId.eq(vehicle_id))
+        .filter(entity::vehicle_incident::Column::ClearedAt.is_null())
+        .order_by_asc(entity::vehicle_incident::Column::OccurredAt)
+        .all(db)
+        .await?;
+
+    Ok(deficiencies
+        .into_iter()
+        .map(|d| format!("Grounding deficiency {} is open: {}", d.id, d.description))
+        .chain(incidents.into_iter().map(|i| {
+            format!(
+                "Incident {} on {} has not been cleared",
+                i.id,
+                i.occurred_at.format("%d %b %Y")
+            )
+        }))
+        .collect())
+}
+
+/// Grounds a vehicle turned in out of service, with a deficiency recording
+/// why so that closing it is what puts the vehicle back in service.
+pub(crate) async fn ground_vehicle<C: ConnectionTrait>(
+    db: &C,
+    vehicle_id: i32,
+    problem: String,
+) -> Result<(), DbErr> {
+    entity::vehicle_deficiency::ActiveModel {
+        id: NotSet,
+        vehicle_id: Set(vehicle_id),
+        inspection_id: Set(None),
+        item_key: Set(TURN_IN.to_string()),
+        description: Set(problem),
+        severity: Set(GROUNDING.to_string()),
+        assigned_to: Set(None),
+        opened_at: Set(Local::now().naive_local()),
+        resolution: Set(None),
+        closed_by: Set(None),
+        closed_at: Set(None),
+    }
+    .insert(db)
+    .await?;
+
+    Vehicle::update_many()
+        .col_expr(entity::vehicle::Column::InService, Expr::value(false))
+        .filter(entity::vehicle::Column::Id.eq(vehicle_id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Opens a deficiency for each failed item on a signed inspection that isn't
+/// already being tracked, and takes the vehicle out of service if any of the
+/// failures are grounding.
+pub(crate) async fn open_vehicle_deficiencies<C: ConnectionTrait>(
+    db: &C,
+    inspection: &entity::vehicle_inspection::Model,
+    checklist: &[ChecklistItem],
+) -> Result<(), DbErr> {
+    let tracked: Vec<String> = open_deficiencies(db, inspection.vehicle_id)
+        .await?
+        .into_iter()
+        .map(|d| d.item_key)
+        .collect();
+
+    let failed: Vec<&ChecklistItem> = checklist
+        .iter()
+        .filter(|item| item.passed == Some(false))
+        .collect();
+
+    let deficiencies: Vec<entity::vehicle_deficiency::ActiveModel> = failed
+        .iter()
+        .filter(|item| !tracked.contains(&item.key))
+        .map(|item| entity::vehicle_deficiency::ActiveModel {
+            id: NotSet,
+            vehicle_id: Set(inspection.vehicle_id),
+            inspection_id: Set(Some(inspection.id)),
+            item_key: Set(item.key.clone()),
+            description: Set(item.remarks.clone().unwrap_or_else(|| item.label.clone())),
+            severity: Set(item.severity.clone()),
+            assigned_to: Set(None),
+            opened_at: Set(Local::now().naive_local()),
+            resolution: Set(None),
+            closed_by: Set(None),
+            closed_at: Set(None),
+        })
+        .collect();
+
+    if !deficiencies.is_empty() {
+        VehicleDeficiency::insert_many(deficiencies)
+            .exec(db)
+            .await?;
+    }
+
+    if failed.iter().any(|item| item.severity == GROUNDING) {
+        Vehicle::update_many()
+            .col_expr(entity::vehicle::Column::InService, Expr::value(false))
+            .filter(entity::vehicle::Column::Id.eq(inspection.vehicle_id))
+            .exec(db)
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn get_all_vehicle_deficiencies(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<entity::vehicle_deficiency::ModelEx>>, StatusCode> {
+    let mut query = VehicleDeficiency::load()
+        .with(Vehicle)
+        .with(Attendee)
+        .order_by_desc(entity::vehicle_deficiency::Column::OpenedAt);
+
+    if let Some(s) = params.get("vehicleId") {
+        match s.parse::<i32>() {
+            Ok(id) => query = query.filter(entity::vehicle_deficiency::Column::VehicleId.eq(id)),
+            Err(_) => return Err(StatusCode::BAD_REQUEST),
+        }
+    }
+
+    if let Some(s) = params.get("assignedTo") {
+        match s.parse::<i32>() {
+            Ok(capid) => {
+                query = query.filter(entity::vehicle_deficiency::Column::AssignedTo.eq(capid))
+            }
+            Err(_) => return Err(StatusCode::BAD_REQUEST),
+        }
+    }
+
+    match params.get("status").map(String::as_str) {
+        Some("open") => {
+            query = query.filter(entity::vehicle_deficiency::Column::ClosedAt.is_null())
+        }
+        Some("closed") => {
+            query = query.filter(entity::vehicle_deficiency::Column::ClosedAt.is_not_null())
+        }
+        Some(_) => return Err(StatusCode::BAD_REQUEST),
+        None => {}
+    }
+
+    match query.all(&state.db).await {
+        Ok(deficiencies) => Ok(Json(deficiencies)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn get_vehicle_deficiency(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<entity::vehicle_deficiency::ModelEx>, StatusCode> {
+    match VehicleDeficiency::load()
+        .filter_by_id(id)
+        .with(Vehicle)
+        .with(Attendee)
+        .one(&state.db)
+        .await
+    {
+        Ok(Some(deficiency)) => Ok(Json(deficiency)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UpdateDeficiencyParameters {
+    severity: Option<String>,
+    assigned_to: Option<i32>,
+}
+
+pub(crate) async fn update_vehicle_deficiency(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<UpdateDeficiencyParameters>,
+) -> impl IntoResponse {
+    let deficiency = match VehicleDeficiency::find_by_id(id).one(&state.db).await {
+        Ok(Some(deficiency)) => deficiency,
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    if deficiency.closed_at.is_some() {
+        return StatusCode::CONFLICT;
+    }
+
+    let vehicle_id = deficiency.vehicle_id;
+    let mut model = deficiency.into_active_model();
+
+    if let Some(severity) = &data.severity {
+        if !is_severity(severity) {
+            return StatusCode::BAD_REQUEST;
+        }
+        model.severity = Set(severity.clone());
+    }
+    if data.assigned_to.is_some() {
+        model.assigned_to = Set(data.assigned_to);
+    }
+
+    match model.update(&state.db).await {
+        Ok(_) => {}
+        Err(DbErr::Query(_)) => return StatusCode::BAD_REQUEST,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    if data.severity.as_deref() != Some(GROUNDING) {
+        return StatusCode::OK;
+    }
+
+    match Vehicle::update_many()
+        .col_expr(entity::vehicle::Column::InService, Expr::value(false))
+        .filter(entity::vehicle::Column::Id.eq(vehicle_id))
+        .exec(&state.db)
+        .await
+    {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CloseDeficiencyParameters {
+    capid: i32,
+    resolution: String,
+}
+
+pub(crate) async fn close_vehicle_deficiency(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<CloseDeficiencyParameters>,
+) -> impl IntoResponse {
+    if data.resolution.trim().is_empty() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let deficiency = match VehicleDeficiency::find_by_id(id).one(&state.db).await {
+        Ok(Some(deficiency)) => deficiency,
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    if deficiency.closed_at.is_some() {
+        return StatusCode::CONFLICT;
+    }
+
+    let vehicle_id = deficiency.vehicle_id;
+    let grounding = deficiency.severity == GROUNDING;
+    let mut model = deficiency.into_active_model();
+
+    model.resolution = Set(Some(data.resolution));
+    model.closed_by = Set(Some(data.capid));
+    model.closed_at = Set(Some(Local::now().naive_local()));
+
+    match model.update(&state.db).await {
+        Ok(_) => {}
+        Err(DbErr::Query(_)) => return StatusCode::BAD_REQUEST,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    if !grounding {
+        return StatusCode::OK;
+    }
+
+    // The vehicle stays grounded while anything else is still holding it out
+    match service_holds(&state.db, vehicle_id).await {
+        Ok(holds) if holds.is_empty() => {}
+        Ok(_) => return StatusCode::OK,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    match Vehicle::update_many()
+        .col_expr(entity::vehicle::Column::InService, Expr::value(true))
+        .filter(entity::vehicle::Column::Id.eq(vehicle_id))
+        .exec(&state.db)
+        .await
+    {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn delete_vehicle_deficiency(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    match VehicleDeficiency::find_by_id(id).one(&state.db).await {
+        Ok(Some(deficiency)) if deficiency.closed_at.is_none() => return StatusCode::CONFLICT,
+        Ok(Some(_)) => {}
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    match VehicleDeficiency::delete_by_id(id).exec(&state.db).await {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
@@ -1,13 +1,37 @@
 use sea_orm::DatabaseConnection;
 
+use crate::config::Config;
+
+pub(crate) mod approvals;
+pub(crate) mod assets;
 pub(crate) mod attendees;
+pub(crate) mod calendars;
+pub(crate) mod callsigns;
+pub(crate) mod conflicts;
+pub(crate) mod drivers;
+pub(crate) mod facilities;
+pub(crate) mod flights;
 pub(crate) mod headcount;
+pub(crate) mod incidents;
+pub(crate) mod inspections;
+pub(crate) mod locations;
+pub(crate) mod lodging;
+pub(crate) mod maintenance;
+pub(crate) mod notices;
+pub(crate) mod notifications;
+pub(crate) mod plans;
 pub(crate) mod radios;
+pub(crate) mod receipts;
+pub(crate) mod reservations;
 pub(crate) mod root;
+pub(crate) mod schedules;
+pub(crate) mod trips;
 pub(crate) mod uploads;
 pub(crate) mod vehicles;
+pub(crate) mod versions;
 
 pub(crate) struct AppState {
     pub(crate) db: DatabaseConnection,
     pub(crate) version: String,
+    pub(crate) config: Config,
 }
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use chrono::{Local, NaiveDate};
+use entity::prelude::Notice;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, EntityTrait, QueryFilter, QueryOrder,
+};
+use serde::Deserialize;
+
+use crate::AppState;
+
+#[derive(Deserialize)]
+pub(crate) struct NoticeParameters {
+    date: NaiveDate,
+    title: String,
+    body: String,
+}
+
+pub(crate) async fn get_all_notices(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<entity::notice::Model>>, StatusCode> {
+    let mut query = Notice::find()
+        .order_by_asc(entity::notice::Column::Date)
+        .order_by_asc(entity::notice::Column::CreatedAt);
+
+    if let Some(s) = params.get("date") {
+        let date: NaiveDate = s.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+        query = query.filter(entity::notice::Column::Date.eq(date));
+    }
+
+    match query.all(&state.db).await {
+        Ok(notices) => Ok(Json(notices)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn create_notice(
+    State(state): State<Arc<AppState>>,
+    Json(data): Json<NoticeParameters>,
+) -> Result<Json<entity::notice::Model>, StatusCode> {
+    if data.title.trim().is_empty() || data.body.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let model = entity::notice::ActiveModel {
+        id: NotSet,
+        date: Set(data.date),
+        title: Set(data.title.trim().to_string()),
+        body: Set(data.body.trim().to_string()),
+        created_at: Set(Local::now().naive_local()),
+    };
+
+    match model.insert(&state.db).await {
+        Ok(notice) => Ok(Json(notice)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn delete_notice(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    match Notice::delete_by_id(id).exec(&state.db).await {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
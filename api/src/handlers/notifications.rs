@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use chrono::{Local, NaiveDateTime};
+use entity::prelude::{FlightMember, Notification, NotificationRead};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, DbConn, DbErr, EntityTrait, ExprTrait,
+    QueryFilter, QueryOrder, SqlErr,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::AppState;
+
+#[derive(Serialize)]
+pub(crate) struct NotificationListing {
+    #[serde(flatten)]
+    notification: entity::notification::Model,
+    read_at: Option<NaiveDateTime>,
+}
+
+async fn flights(db: &DbConn, capid: i32) -> Result<Vec<i32>, DbErr> {
+    Ok(FlightMember::find()
+        .filter(entity::flight_member::Column::Capid.eq(capid))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|m| m.flight_id)
+        .collect())
+}
+
+/// Notifications, newest first. `?capid=` gets an attendee's own along with
+/// their flight's, each marked with when that attendee read it, and
+/// `?unread=true` leaves out the ones they've read. Read state is per
+/// person, so `unread` needs a `capid`.
+pub(crate) async fn get_all_notifications(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<NotificationListing>>, StatusCode> {
+    let mut query = Notification::find().order_by_desc(entity::notification::Column::CreatedAt);
+    let mut reads: HashMap<i32, NaiveDateTime> = HashMap::new();
+
+    let unread = params.get("unread").is_some_and(|s| s == "true");
+
+    match params.get("capid") {
+        Some(s) => {
+            let capid: i32 = s.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+            let flights = flights(&state.db, capid)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            query = query.filter(
+                entity::notification::Column::Capid
+                    .eq(capid)
+                    .or(entity::notification::Column::FlightId.is_in(flights)),
+            );
+
+            reads = NotificationRead::find()
+                .filter(entity::notification_read::Column::Capid.eq(capid))
+                .all(&state.db)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+                .into_iter()
+                .map(|r| (r.notification_id, r.read_at))
+                .collect();
+        }
+        None if unread => return Err(StatusCode::BAD_REQUEST),
+        None => {}
+    }
+
+    if let Some(s) = params.get("flightId") {
+        let id: i32 = s.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+        query = query.filter(entity::notification::Column::FlightId.eq(id));
+    }
+
+    if unread {
+        query = query.filter(entity::notification::Column::Id.is_not_in(reads.keys().copied()));
+    }
+
+    match query.all(&state.db).await {
+        Ok(notifications) => Ok(Json(
+            notifications
+                .into_iter()
+                .map(|notification| NotificationListing {
+                    read_at: reads.get(&notification.id).copied(),
+                    notification,
+                })
+                .collect(),
+        )),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ReadParameters {
+    capid: i32,
+}
+
+/// Marks a notification read for one recipient. A flight notification stays
+/// unread for the rest of the flight.
+pub(crate) async fn mark_notification_read(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<ReadParameters>,
+) -> impl IntoResponse {
+    let notification = match Notification::find_by_id(id).one(&state.db).await {
+        Ok(Some(notification)) => notification,
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    let recipient = match notification.flight_id {
+        _ if notification.capid == Some(data.capid) => true,
+        Some(flight_id) => match flights(&state.db, data.capid).await {
+            Ok(flights) => flights.contains(&flight_id),
+            Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+        },
+        None => false,
+    };
+    if !recipient {
+        return StatusCode::FORBIDDEN;
+    }
+
+    let model = entity::notification_read::ActiveModel {
+        id: NotSet,
+        notification_id: Set(id),
+        capid: Set(data.capid),
+        read_at: Set(Local::now().naive_local()),
+    };
+
+    match model.insert(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(e) => match e.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => StatusCode::OK,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        },
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use chrono::{Local, NaiveDate};
+use entity::prelude::{Activity, Attendee, Flight, Location, Notice, ScheduleDay, TimeBlock};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::AppState;
+use crate::handlers::schedules::live_blocks;
+use crate::pdf::{self, Document};
+
+/// The plan of the day for one date, `?date=` (today by default), from the
+/// approved schedules. `?flightId=` or `?squadron=` narrows it to one board,
+/// and `?notices=true` adds the day's notices at the end.
+pub(crate) async fn get_plan_of_the_day(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let date: NaiveDate = match params.get("date") {
+        Some(s) => s.parse().map_err(|_| StatusCode::BAD_REQUEST)?,
+        None => Local::now().date_naive(),
+    };
+
+    let flight_id: Option<i32> = match params.get("flightId") {
+        Some(s) => Some(s.parse().map_err(|_| StatusCode::BAD_REQUEST)?),
+        None => None,
+    };
+    let squadron = params.get("squadron");
+    if flight_id.is_some() && squadron.is_some() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let live = live_blocks(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let days = ScheduleDay::find()
+        .filter(entity::schedule_day::Column::ScheduleId.is_in(live.values().map(|s| s.id)))
+        .filter(entity::schedule_day::Column::Date.eq(date))
+        .all(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let blocks: Vec<i32> = TimeBlock::find()
+        .filter(entity::time_block::Column::DayId.is_in(days.iter().map(|d| d.id)))
+        .all(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .into_iter()
+        .map(|b| b.id)
+        .collect();
+
+    let mut activities = Activity::load()
+        .filter(entity::activity::Column::BlockId.is_in(blocks))
+        .with(TimeBlock)
+        .with(Location)
+        .with(Flight)
+        .with(Attendee)
+        .order_by_asc(entity::activity::Column::Id)
+        .all(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let board = match (flight_id, squadron) {
+        (Some(id), _) => {
+            activities.retain(|a| a.flights.iter().any(|f| f.id == id));
+            match Flight::find_by_id(id).one(&state.db).await {
+                Ok(Some(flight)) => Some(("Flight", flight.name)),
+                Ok(None) => return Err(StatusCode::NOT_FOUND),
+                Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+            }
+        }
+        (None, Some(squadron)) => {
+            activities.retain(|a| a.flights.iter().any(|f| &f.squadron == squadron));
+            Some(("Squadron", squadron.clone()))
+        }
+        (None, None) => None,
+    };
+
+    activities.sort_by_key(|a| a.block.as_ref().map(|b| b.starts_at));
+
+    let rows: Vec<Vec<String>> = activities
+        .into_iter()
+        .map(|activity| {
+            let time = match activity.block.as_ref() {
+                Some(block) => format!(
+                    "{}-{}",
+                    block.starts_at.format("%H%M"),
+                    block.ends_at.format("%H%M")
+                ),
+                None => String::new(),
+            };
+            let flights: Vec<&str> = activity.flights.iter().map(|f| f.name.as_str()).collect();
+            let staff: Vec<String> = activity
+                .staff
+                .iter()
+                .map(|a| format!("{} {}", a.rank, a.last_name))
+                .collect();
+
+            vec![
+                time,
+                activity.name.clone(),
+                activity
+                    .location
+                    .as_ref()
+                    .map(|l| l.name.clone())
+                    .unwrap_or_default(),
+                flights.join(", "),
+                staff.join(", "),
+            ]
+        })
+        .collect();
+
+    let mut uniforms: Vec<String> = days.into_iter().filter_map(|d| d.uniform).collect();
+    uniforms.sort();
+    uniforms.dedup();
+
+    let mut document = Document::new("Plan of the Day");
+    document.field("Date", &date.format("%A, %d %B %Y").to_string());
+    if let Some((label, name)) = &board {
+        document.field(label, name);
+    }
+    if uniforms.is_empty() {
+        document.field("Uniform of the Day", "Not set");
+    } else {
+        document.field("Uniform of the Day", &uniforms.join("; "));
+    }
+
+    document.heading("Schedule");
+    if rows.is_empty() {
+        document.paragraph("Nothing scheduled.");
+    } else {
+        document.table(
+            &[
+                ("Time", 0.12),
+                ("Activity", 0.26),
+                ("Location", 0.18),
+                ("Flights", 0.18),
+                ("Staff", 0.26),
+            ],
+            &rows,
+        );
+    }
+
+    if params.get("notices").is_some_and(|s| s == "true") {
+        let notices = Notice::find()
+            .filter(entity::notice::Column::Date.eq(date))
+            .order_by_asc(entity::notice::Column::CreatedAt)
+            .all(&state.db)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        for notice in notices {
+            document.heading(&notice.title);
+            document.paragraph(&notice.body);
+        }
+    }
+
+    let file_name = match &board {
+        Some((_, name)) => format!(
+            "plan-of-the-day-{date}-{}.pdf",
+            name.to_lowercase().replace(' ', "-")
+        ),
+        None => format!("plan-of-the-day-{date}.pdf"),
+    };
+
+    Ok(pdf::response(&file_name, document.finish()))
+}
@@ -1,14 +1,29 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use axum::extract::{Query, State};
+use axum::extract::{Multipart, Query, State};
 use axum::http::StatusCode;
-use axum::response::{IntoResponse, Json};
-use entity::prelude::{Attendee, Radio, RadioType};
-use sea_orm::{ActiveModelTrait, ActiveValue::*, DbErr, EntityTrait};
-use serde::Deserialize;
+use axum::response::{IntoResponse, Json, Response};
+use entity::prelude::{Attendee, Callsign, Radio, RadioType};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, DbConn, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, SqlErr, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
 
 use crate::AppState;
+use crate::handlers::assets::tag_in_use;
+use crate::handlers::drivers;
+use crate::handlers::maintenance::{open_radio_ticket, open_radio_tickets};
+use crate::handlers::receipts::{self, Custody, Item};
+use crate::import::{ImportReport, Row, read_rows, save_upload};
+
+#[derive(Serialize)]
+pub(crate) struct RadioListing {
+    #[serde(flatten)]
+    radio: entity::radio::ModelEx,
+    callsigns: Vec<String>,
+}
 
 pub(crate) async fn get_all_radios(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     let radios = entity::radio::Entity::load()
@@ -18,13 +33,45 @@ pub(crate) async fn get_all_radios(State(state): State<Arc<AppState>>) -> impl I
         .await
         .expect("Could not get radios");
 
-    Json(radios)
+    // Someone can hold more than one callsign, so the holder's radio lists all
+    // of them
+    let mut callsigns: HashMap<i32, Vec<String>> = HashMap::new();
+    for callsign in Callsign::find()
+        .filter(entity::callsign::Column::AssignedTo.is_not_null())
+        .order_by_asc(entity::callsign::Column::Name)
+        .all(&state.db)
+        .await
+        .expect("Could not get callsigns")
+    {
+        if let Some(capid) = callsign.assigned_to {
+            callsigns.entry(capid).or_default().push(callsign.name);
+        }
+    }
+
+    let listings: Vec<RadioListing> = radios
+        .into_iter()
+        .map(|radio| RadioListing {
+            callsigns: radio
+                .issued_to
+                .and_then(|capid| callsigns.get(&capid).cloned())
+                .unwrap_or_default(),
+            radio,
+        })
+        .collect();
+
+    Json(listings)
 }
 
 pub(crate) async fn create_new_radio(
     State(state): State<Arc<AppState>>,
     Json(radio): Json<entity::radio::Model>,
 ) -> impl IntoResponse {
+    match tag_in_use(&state.db, &radio.property_tag_number).await {
+        Ok(false) => {}
+        Ok(true) => return StatusCode::CONFLICT,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
     let active_model = entity::radio::ActiveModel {
         id: NotSet,
         type_id: Set(radio.type_id),
@@ -37,6 +84,9 @@ pub(crate) async fn create_new_radio(
     match active_model.insert(&state.db).await {
         Ok(_) => StatusCode::OK,
         Err(DbErr::RecordNotInserted) => StatusCode::BAD_REQUEST,
+        Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+            StatusCode::CONFLICT
+        }
         Err(e) => {
             println!("{e:#?}");
             StatusCode::INTERNAL_SERVER_ERROR
@@ -44,6 +94,93 @@ pub(crate) async fn create_new_radio(
     }
 }
 
+pub(crate) async fn create_radio_bulk(
+    State(state): State<Arc<AppState>>,
+    mut multipart: Multipart,
+) -> Result<Json<ImportReport>, StatusCode> {
+    let upload = save_upload(&mut multipart).await?;
+    let rows = read_rows(&upload).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let mut types: HashMap<(String, String), i32> = RadioType::find()
+        .all(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .into_iter()
+        .map(|t| ((t.make.to_lowercase(), t.model.to_lowercase()), t.id))
+        .collect();
+
+    let mut report = ImportReport::default();
+
+    for row in rows {
+        let result = import_radio(&state.db, &row, &mut types).await;
+        report.record(row.number, result);
+    }
+
+    Ok(Json(report))
+}
+
+async fn import_radio(
+    db: &DbConn,
+    row: &Row,
+    types: &mut HashMap<(String, String), i32>,
+) -> Result<(), String> {
+    let make = row.require(&["make"])?;
+    let model = row.require(&["model"])?;
+    let property_tag_number = row.require(&["property tag", "property tag number", "tag"])?;
+    let owned_by_unit = row.require(&["owned by unit", "unit"])?;
+    let in_service = row.flag(&["in service"], true)?;
+
+    if tag_in_use(db, property_tag_number)
+        .await
+        .map_err(|e| e.to_string())?
+    {
+        return Err(format!(
+            "Property tag {property_tag_number} is already in use"
+        ));
+    }
+
+    // A new type only sticks if the row's radio does, so a failed row doesn't
+    // leave one behind for later rows to pick up
+    let txn = db.begin().await.map_err(|e| e.to_string())?;
+
+    let key = (make.to_lowercase(), model.to_lowercase());
+    let (type_id, new_type) = match types.get(&key) {
+        Some(id) => (*id, false),
+        None => {
+            let radio_type = entity::radio_type::ActiveModel {
+                id: NotSet,
+                make: Set(make.to_string()),
+                model: Set(model.to_string()),
+            }
+            .insert(&txn)
+            .await
+            .map_err(|e| format!("Could not create radio type {make} {model}: {e}"))?;
+
+            (radio_type.id, true)
+        }
+    };
+
+    entity::radio::ActiveModel {
+        id: NotSet,
+        type_id: Set(type_id),
+        property_tag_number: Set(property_tag_number.to_string()),
+        owned_by_unit: Set(owned_by_unit.to_string()),
+        issued_to: Set(None),
+        in_service: Set(in_service),
+    }
+    .insert(&txn)
+    .await
+    .map_err(|e| format!("Could not save radio {property_tag_number}: {e}"))?;
+
+    txn.commit().await.map_err(|e| e.to_string())?;
+
+    if new_type {
+        types.insert(key, type_id);
+    }
+
+    Ok(())
+}
+
 pub(crate) async fn delete_radio(
     State(state): State<Arc<AppState>>,
     Query(params): Query<HashMap<String, String>>,
@@ -64,6 +201,10 @@ pub(crate) async fn delete_radio(
     match model.delete(&state.db).await {
         Ok(_) => StatusCode::OK,
         Err(DbErr::RecordNotFound(_)) => StatusCode::BAD_REQUEST,
+        // A radio with receipts or tickets on file is kept for the record
+        Err(e) if matches!(e.sql_err(), Some(SqlErr::ForeignKeyConstraintViolation(_))) => {
+            StatusCode::CONFLICT
+        }
         _ => StatusCode::INTERNAL_SERVER_ERROR,
     }
 }
@@ -121,33 +262,82 @@ pub(crate) async fn delete_radio_type(
 #[derive(Deserialize)]
 pub(crate) struct IssueRadioParameters {
     radio_id: i32,
-    capid: i32,
+    #[serde(flatten)]
+    custody: Custody,
 }
 
 pub(crate) async fn issue_radio(
     State(state): State<Arc<AppState>>,
     Json(data): Json<IssueRadioParameters>,
 ) -> impl IntoResponse {
-    let model = match Radio::find_by_id(data.radio_id).one(&state.db).await {
+    issue(&state.db, data.radio_id, data.custody).await
+}
+
+/// Hands a radio out. A radio that's out of service or has a ticket still
+/// open against it is refused with a 409 listing why.
+pub(crate) async fn issue(
+    db: &DbConn,
+    radio_id: i32,
+    custody: Custody,
+) -> Result<Json<entity::hand_receipt::Model>, Response> {
+    let model = match Radio::find_by_id(radio_id).one(db).await {
         Ok(opt) => match opt {
             Some(model) => model,
-            None => return StatusCode::BAD_REQUEST,
+            None => return Err(StatusCode::BAD_REQUEST.into_response()),
         },
-        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response()),
     };
 
+    let mut reasons: Vec<String> = open_radio_tickets(db, radio_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?
+        .into_iter()
+        .map(|t| {
+            format!(
+                "Ticket {} is {}: {}",
+                t.id,
+                t.status.replace('_', " "),
+                t.problem
+            )
+        })
+        .collect();
+    if !model.in_service && reasons.is_empty() {
+        reasons.push("Radio is out of service".to_string());
+    }
+    if !reasons.is_empty() {
+        return Err(drivers::refuse_with(StatusCode::CONFLICT, reasons));
+    }
+
+    let txn = db
+        .begin()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+
     let active_model = entity::radio::ActiveModel {
         id: Unchanged(model.id),
         type_id: Unchanged(model.type_id),
         property_tag_number: Unchanged(model.property_tag_number),
         owned_by_unit: Unchanged(model.owned_by_unit),
-        issued_to: Set(Some(data.capid)),
+        issued_to: Set(Some(custody.capid)),
         in_service: Unchanged(model.in_service),
     };
 
-    match active_model.update(&state.db).await {
-        Ok(_) => StatusCode::OK,
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    if active_model.update(&txn).await.is_err() {
+        return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+    }
+
+    // Reissuing without a turn-in hands custody straight to the new holder
+    receipts::close(&txn, Item::Radio(radio_id))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+
+    let receipt = receipts::open(&txn, Item::Radio(radio_id), custody)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+
+    match txn.commit().await {
+        Ok(_) => Ok(Json(receipt)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR.into_response()),
     }
 }
 
@@ -171,7 +361,18 @@ pub(crate) async fn return_radio(
         None => true,
     };
 
-    let model = match Radio::find_by_id(id).one(&state.db).await {
+    turn_in(&state.db, id, in_service, params.get("problem").cloned()).await
+}
+
+/// Takes a radio back. Turning it in serviceable doesn't close any ticket
+/// still open against it; turning it in unserviceable opens one.
+pub(crate) async fn turn_in(
+    db: &DbConn,
+    radio_id: i32,
+    in_service: bool,
+    problem: Option<String>,
+) -> StatusCode {
+    let model = match Radio::find_by_id(radio_id).one(db).await {
         Ok(opt) => match opt {
             Some(model) => model,
             None => return StatusCode::BAD_REQUEST,
@@ -179,16 +380,35 @@ pub(crate) async fn return_radio(
         Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
     };
 
+    let tickets = match open_radio_tickets(db, radio_id).await {
+        Ok(tickets) => tickets,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
     let active_model = entity::radio::ActiveModel {
         id: Unchanged(model.id),
         type_id: Unchanged(model.type_id),
         property_tag_number: Unchanged(model.property_tag_number),
         owned_by_unit: Unchanged(model.owned_by_unit),
         issued_to: Set(None),
-        in_service: Set(in_service),
+        in_service: Set(in_service && tickets.is_empty()),
     };
 
-    match active_model.update(&state.db).await {
+    if active_model.update(db).await.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    if receipts::close(db, Item::Radio(radio_id)).await.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    if in_service {
+        return StatusCode::OK;
+    }
+
+    let problem = problem.unwrap_or_else(|| "Returned out of service".to_string());
+
+    match open_radio_ticket(db, radio_id, problem, model.issued_to).await {
         Ok(_) => StatusCode::OK,
         Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
     }
@@ -0,0 +1,329 @@
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use axum::extract::{Multipart, Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use chrono::Local;
+use entity::prelude::{Attendee, HandReceipt, Radio, RadioType, Vehicle, VehicleType};
+use png::Decoder;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, ConnectionTrait, DbErr, EntityLoaderTrait,
+    EntityTrait, QueryFilter, QueryOrder,
+};
+use serde::Deserialize;
+
+use crate::AppState;
+use crate::pdf::{self, Document};
+
+pub(crate) enum Item {
+    Radio(i32),
+    Vehicle(i32),
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Custody {
+    pub(crate) capid: i32,
+    pub(crate) issued_by: Option<i32>,
+    pub(crate) condition: Option<String>,
+}
+
+pub(crate) async fn open<C: ConnectionTrait>(
+    db: &C,
+    item: Item,
+    custody: Custody,
+) -> Result<entity::hand_receipt::Model, DbErr> {
+    let (radio_id, vehicle_id) = match item {
+        Item::Radio(id) => (Some(id), None),
+        Item::Vehicle(id) => (None, Some(id)),
+    };
+
+    entity::hand_receipt::ActiveModel {
+        id: NotSet,
+        radio_id: Set(radio_id),
+        vehicle_id: Set(vehicle_id),
+        issued_to: Set(custody.capid),
+        issued_by: Set(custody.issued_by),
+        condition: Set(custody
+            .condition
+            .unwrap_or_else(|| "Serviceable".to_string())),
+        issued_at: Set(Local::now().naive_local()),
+        signed_name: Set(None),
+        signature_file: Set(None),
+        signed_at: Set(None),
+        returned_at: Set(None),
+    }
+    .insert(db)
+    .await
+}
+
+pub(crate) async fn close<C: ConnectionTrait>(db: &C, item: Item) -> Result<(), DbErr> {
+    let column = match item {
+        Item::Radio(id) => entity::hand_receipt::Column::RadioId.eq(id),
+        Item::Vehicle(id) => entity::hand_receipt::Column::VehicleId.eq(id),
+    };
+
+    HandReceipt::update_many()
+        .col_expr(
+            entity::hand_receipt::Column::ReturnedAt,
+            Expr::value(Local::now().naive_local()),
+        )
+        .filter(column)
+        .filter(entity::hand_receipt::Column::ReturnedAt.is_null())
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+pub(crate) async fn get_all_receipts(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let receipts = HandReceipt::load()
+        .with(Attendee)
+        .with(Radio)
+        .with(Vehicle)
+        .order_by_desc(entity::hand_receipt::Column::IssuedAt)
+        .all(&state.db)
+        .await
+        .expect("Could not get hand receipts");
+
+    Json(receipts)
+}
+
+pub(crate) async fn get_receipt(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<entity::hand_receipt::ModelEx>, StatusCode> {
+    match HandReceipt::load()
+        .filter_by_id(id)
+        .with(Attendee)
+        .with((Radio, RadioType))
+        .with((Vehicle, VehicleType))
+        .one(&state.db)
+        .await
+    {
+        Ok(Some(model)) => Ok(Json(model)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn sign_receipt(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    // A receipt is signed once; the signature on file is the custody record
+    match HandReceipt::find_by_id(id).one(&state.db).await {
+        Ok(Some(receipt)) if receipt.signed_at.is_some() => return StatusCode::CONFLICT,
+        Ok(Some(_)) => {}
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    let mut signed_name = None;
+    let mut signature_file = None;
+
+    while let Ok(Some(field)) = multipart.next_field().await {
+        match field.name() {
+            Some("name") => match field.text().await {
+                Ok(name) if !name.trim().is_empty() => signed_name = Some(name.trim().to_string()),
+                Ok(_) => {}
+                Err(_) => return StatusCode::BAD_REQUEST,
+            },
+            Some("signature") => {
+                if field.content_type() != Some("image/png") {
+                    return StatusCode::BAD_REQUEST;
+                }
+
+                let data = match field.bytes().await {
+                    Ok(data) if decodes_as_png(&data) => data,
+                    _ => return StatusCode::BAD_REQUEST,
+                };
+
+                let file_name = format!(
+                    "{}_receipt_{id}_signature.png",
+                    SystemTime::now()
+                        .duration_since(SystemTime::UNIX_EPOCH)
+                        .unwrap()
+                        .as_millis()
+                );
+                let mut path = PathBuf::from(dotenvy::var("UPLOADS_PATH").unwrap());
+                path.push(&file_name);
+
+                match tokio::fs::write(&path, data).await {
+                    Ok(_) => signature_file = Some(file_name),
+                    Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if signed_name.is_none() && signature_file.is_none() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    // Only the first of two signings racing each other lands
+    match HandReceipt::update_many()
+        .col_expr(
+            entity::hand_receipt::Column::SignedName,
+            Expr::value(signed_name),
+        )
+        .col_expr(
+            entity::hand_receipt::Column::SignatureFile,
+            Expr::value(signature_file),
+        )
+        .col_expr(
+            entity::hand_receipt::Column::SignedAt,
+            Expr::value(Local::now().naive_local()),
+        )
+        .filter(entity::hand_receipt::Column::Id.eq(id))
+        .filter(entity::hand_receipt::Column::SignedAt.is_null())
+        .exec(&state.db)
+        .await
+    {
+        Ok(res) if res.rows_affected == 0 => StatusCode::CONFLICT,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// The content type is only what the client claims, so the signature has to
+/// actually decode before it's kept.
+fn decodes_as_png(data: &[u8]) -> bool {
+    let mut reader = match Decoder::new(Cursor::new(data)).read_info() {
+        Ok(reader) => reader,
+        Err(_) => return false,
+    };
+
+    match reader.output_buffer_size() {
+        Some(size) => reader.next_frame(&mut vec![0; size]).is_ok(),
+        None => false,
+    }
+}
+
+pub(crate) async fn get_receipt_pdf(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let receipt = match HandReceipt::load()
+        .filter_by_id(id)
+        .with(Attendee)
+        .with((Radio, RadioType))
+        .with((Vehicle, VehicleType))
+        .one(&state.db)
+        .await
+    {
+        Ok(Some(model)) => model,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let issuer = match receipt.issued_by {
+        Some(capid) => Attendee::find_by_id(capid)
+            .one(&state.db)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+        None => None,
+    };
+
+    let (item, tag, unit) = if let Some(radio) = receipt.radio.as_ref() {
+        let description = match radio.radio_type.as_ref() {
+            Some(t) => format!("Radio, {} {}", t.make, t.model),
+            None => "Radio".to_string(),
+        };
+        (
+            description,
+            radio.property_tag_number.clone(),
+            radio.owned_by_unit.clone(),
+        )
+    } else if let Some(vehicle) = receipt.vehicle.as_ref() {
+        let description = match vehicle.vehicle_type.as_ref() {
+            Some(t) => format!(
+                "Vehicle {}, {} {} {}",
+                vehicle.id, vehicle.year, t.make, t.model
+            ),
+            None => format!("Vehicle {}", vehicle.id),
+        };
+        (
+            description,
+            vehicle.property_tag_number.clone().unwrap_or_default(),
+            vehicle.owned_by_unit.clone(),
+        )
+    } else {
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    };
+
+    let describe = |a: &entity::attendee::ModelEx| {
+        format!(
+            "{} {} {} (CAPID {})",
+            a.rank, a.first_name, a.last_name, a.capid
+        )
+    };
+
+    let mut document = Document::new("Hand Receipt");
+    document.field("Receipt number", &receipt.id.to_string());
+    document.field(
+        "Issued",
+        &receipt.issued_at.format("%d %b %Y %H:%M").to_string(),
+    );
+
+    document.heading("Item");
+    document.field("Description", &item);
+    document.field("Property tag", &tag);
+    document.field("Owned by unit", &unit);
+    document.field("Condition at issue", &receipt.condition);
+
+    document.heading("Custody");
+    document.field(
+        "Issued by",
+        &issuer
+            .map(|a| describe(&a.into()))
+            .unwrap_or_else(|| "Not recorded".to_string()),
+    );
+    document.field(
+        "Received by",
+        &receipt
+            .receiver
+            .as_ref()
+            .map(describe)
+            .unwrap_or_else(|| receipt.issued_to.to_string()),
+    );
+    if let Some(returned_at) = receipt.returned_at {
+        document.field(
+            "Returned",
+            &returned_at.format("%d %b %Y %H:%M").to_string(),
+        );
+    }
+
+    document.heading("Acknowledgement");
+    document.paragraph(
+        "I acknowledge receipt of the item listed above in the condition stated and accept \
+         responsibility for its care, security and return at the end of the issue period.",
+    );
+
+    if let Some(file_name) = &receipt.signature_file {
+        let mut path = PathBuf::from(dotenvy::var("UPLOADS_PATH").unwrap());
+        path.push(file_name);
+
+        if let Ok(png) = tokio::fs::read(path).await {
+            // A damaged signature image shouldn't stop the receipt printing
+            let _ = document.image(&png, 200.0, 60.0);
+        }
+    }
+
+    let caption = match (&receipt.signed_name, receipt.signed_at) {
+        (Some(name), Some(at)) => format!("/s/ {name}, {}", at.format("%d %b %Y %H:%M")),
+        (None, Some(at)) => format!("Signed {}", at.format("%d %b %Y %H:%M")),
+        _ => "Signature of receiver and date".to_string(),
+    };
+    document.signature_line(&caption);
+
+    Ok(pdf::response(
+        &format!("hand-receipt-{}.pdf", receipt.id),
+        document.finish(),
+    ))
+}
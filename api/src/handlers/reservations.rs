@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeDelta};
+use entity::prelude::{Attendee, Vehicle, VehicleReservation, VehicleTrip, VehicleType};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, ConnectionTrait, EntityLoaderTrait, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::AppState;
+
+#[derive(Deserialize)]
+pub(crate) struct ReservationParameters {
+    vehicle_id: i32,
+    requested_by: i32,
+    purpose: String,
+    destination: String,
+    passengers: i32,
+    starts_at: NaiveDateTime,
+    ends_at: NaiveDateTime,
+}
+
+/// Checks the window, the vehicle's seating and that nobody else has the
+/// vehicle booked for any part of the window.
+///
+/// Run inside a transaction: the vehicle's row stays locked until it ends, so
+/// a second booking for the same vehicle waits and then sees this one.
+async fn check<C: ConnectionTrait>(
+    db: &C,
+    data: &ReservationParameters,
+    existing: Option<i32>,
+) -> Result<(), StatusCode> {
+    if data.ends_at <= data.starts_at || data.passengers < 1 || data.purpose.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let vehicle = match Vehicle::find_by_id(data.vehicle_id)
+        .lock_exclusive()
+        .one(db)
+        .await
+    {
+        Ok(Some(vehicle)) => vehicle,
+        Ok(None) => return Err(StatusCode::BAD_REQUEST),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    match VehicleType::find_by_id(vehicle.type_id).one(db).await {
+        Ok(Some(vehicle_type)) if data.passengers > vehicle_type.capacity => {
+            return Err(StatusCode::UNPROCESSABLE_ENTITY);
+        }
+        Ok(_) => {}
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+
+    let mut overlapping = VehicleReservation::find()
+        .filter(entity::vehicle_reservation::Column::VehicleId.eq(data.vehicle_id))
+        .filter(entity::vehicle_reservation::Column::StartsAt.lt(data.ends_at))
+        .filter(entity::vehicle_reservation::Column::EndsAt.gt(data.starts_at));
+
+    if let Some(id) = existing {
+        overlapping = overlapping.filter(entity::vehicle_reservation::Column::Id.ne(id));
+    }
+
+    match overlapping.count(db).await {
+        Ok(0) => Ok(()),
+        Ok(_) => Err(StatusCode::CONFLICT),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn get_all_reservations(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<entity::vehicle_reservation::ModelEx>>, StatusCode> {
+    let mut query = VehicleReservation::load()
+        .with(Vehicle)
+        .with(Attendee)
+        .order_by_asc(entity::vehicle_reservation::Column::StartsAt);
+
+    if let Some(s) = params.get("vehicleId") {
+        let id: i32 = s.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+        query = query.filter(entity::vehicle_reservation::Column::VehicleId.eq(id));
+    }
+
+    if let Some(s) = params.get("requestedBy") {
+        let capid: i32 = s.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+        query = query.filter(entity::vehicle_reservation::Column::RequestedBy.eq(capid));
+    }
+
+    match query.all(&state.db).await {
+        Ok(reservations) => Ok(Json(reservations)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn create_reservation(
+    State(state): State<Arc<AppState>>,
+    Json(data): Json<ReservationParameters>,
+) -> Result<Json<entity::vehicle_reservation::Model>, StatusCode> {
+    let txn = state
+        .db
+        .begin()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    check(&txn, &data, None).await?;
+
+    let model = entity::vehicle_reservation::ActiveModel {
+        id: NotSet,
+        vehicle_id: Set(data.vehicle_id),
+        requested_by: Set(data.requested_by),
+        purpose: Set(data.purpose),
+        destination: Set(data.destination),
+        passengers: Set(data.passengers),
+        starts_at: Set(data.starts_at),
+        ends_at: Set(data.ends_at),
+        created_at: Set(Local::now().naive_local()),
+    };
+
+    let reservation = model
+        .insert(&txn)
+        .await
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    match txn.commit().await {
+        Ok(_) => Ok(Json(reservation)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn get_reservation(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<entity::vehicle_reservation::ModelEx>, StatusCode> {
+    match VehicleReservation::load()
+        .filter_by_id(id)
+        .with(Vehicle)
+        .with(Attendee)
+        .one(&state.db)
+        .await
+    {
+        Ok(Some(reservation)) => Ok(Json(reservation)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn update_reservation(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<ReservationParameters>,
+) -> impl IntoResponse {
+    match VehicleReservation::find_by_id(id).one(&state.db).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    }
+
+    let txn = match state.db.begin().await {
+        Ok(txn) => txn,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    if let Err(status) = check(&txn, &data, Some(id)).await {
+        return status;
+    }
+
+    let model = entity::vehicle_reservation::ActiveModel {
+        id: Unchanged(id),
+        vehicle_id: Set(data.vehicle_id),
+        requested_by: Set(data.requested_by),
+        purpose: Set(data.purpose),
+        destination: Set(data.destination),
+        passengers: Set(data.passengers),
+        starts_at: Set(data.starts_at),
+        ends_at: Set(data.ends_at),
+        created_at: NotSet,
+    };
+
+    if model.update(&txn).await.is_err() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    match txn.commit().await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn delete_reservation(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    match VehicleReservation::delete_by_id(id).exec(&state.db).await {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct CalendarDay {
+    date: NaiveDate,
+    reservations: Vec<entity::vehicle_reservation::ModelEx>,
+}
+
+/// Reservations laid out day by day, `?start=YYYY-MM-DD&days=N`. A
+/// reservation spanning midnight shows up on each day it covers.
+pub(crate) async fn get_reservation_calendar(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<CalendarDay>>, StatusCode> {
+    let start = match params.get("start") {
+        Some(s) => NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| StatusCode::BAD_REQUEST)?,
+        None => Local::now().date_naive(),
+    };
+    let days: i64 = match params.get("days") {
+        Some(s) => s.parse().map_err(|_| StatusCode::BAD_REQUEST)?,
+        None => 7,
+    };
+    if !(1..=62).contains(&days) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let from = start.and_hms_opt(0, 0, 0).unwrap();
+    let to = from + TimeDelta::days(days);
+
+    let mut query = VehicleReservation::load()
+        .with(Vehicle)
+        .with(Attendee)
+        .filter(entity::vehicle_reservation::Column::StartsAt.lt(to))
+        .filter(entity::vehicle_reservation::Column::EndsAt.gt(from))
+        .order_by_asc(entity::vehicle_reservation::Column::StartsAt);
+
+    if let Some(s) = params.get("vehicleId") {
+        let id: i32 = s.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+        query = query.filter(entity::vehicle_reservation::Column::VehicleId.eq(id));
+    }
+
+    let reservations = query
+        .all(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let calendar = start
+        .iter_days()
+        .take(days as usize)
+        .map(|date| {
+            let day_start = date.and_hms_opt(0, 0, 0).unwrap();
+            let day_end = day_start + TimeDelta::days(1);
+
+            CalendarDay {
+                date,
+                reservations: reservations
+                    .iter()
+                    .filter(|r| r.starts_at < day_end && r.ends_at > day_start)
+                    .cloned()
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Ok(Json(calendar))
+}
+
+#[derive(Serialize)]
+pub(crate) struct IssuePrefill {
+    reservation_id: i32,
+    vehicle_id: i32,
+    capid: i32,
+    purpose: String,
+    destination: String,
+    odometer: Option<i32>,
+    return_date: NaiveDate,
+}
+
+/// Fills in the issue form from the reservation the vehicle is booked under
+/// right now, with the odometer carried over from its last trip.
+pub(crate) async fn get_issue_prefill(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<IssuePrefill>, StatusCode> {
+    let vehicle_id: i32 = match params.get("vehicleId") {
+        Some(s) => s.parse().map_err(|_| StatusCode::BAD_REQUEST)?,
+        None => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    let now = Local::now().naive_local();
+
+    let reservation = match VehicleReservation::find()
+        .filter(entity::vehicle_reservation::Column::VehicleId.eq(vehicle_id))
+        .filter(entity::vehicle_reservation::Column::StartsAt.lte(now))
+        .filter(entity::vehicle_reservation::Column::EndsAt.gt(now))
+        .one(&state.db)
+        .await
+    {
+        Ok(Some(reservation)) => reservation,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let last_trip = VehicleTrip::find()
+        .filter(entity::vehicle_trip::Column::VehicleId.eq(vehicle_id))
+        .order_by_desc(entity::vehicle_trip::Column::DepartedAt)
+        .one(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(IssuePrefill {
+        reservation_id: reservation.id,
+        vehicle_id,
+        capid: reservation.requested_by,
+        purpose: reservation.purpose,
+        destination: reservation.destination,
+        odometer: last_trip.map(|t| t.end_odometer.unwrap_or(t.start_odometer)),
+        return_date: reservation.ends_at.date(),
+    }))
+}
@@ -0,0 +1,801 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use entity::prelude::{
+    Activity, ActivityFlight, ActivityStaff, Attendee, Flight, FlightMember, Headcount, Location,
+    Schedule, ScheduleDay, TimeBlock,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, ConnectionTrait, DbConn, DbErr,
+    EntityLoaderTrait, EntityTrait, QueryFilter, QueryOrder, SqlErr, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::handlers::headcount::{Accountability, accountability};
+
+#[derive(Deserialize)]
+pub(crate) struct ScheduleParameters {
+    name: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ScheduleDayParameters {
+    date: NaiveDate,
+    uniform: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UniformParameters {
+    uniform: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct TimeBlockParameters {
+    name: String,
+    starts_at: NaiveDateTime,
+    ends_at: NaiveDateTime,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ActivityParameters {
+    name: String,
+    description: Option<String>,
+    location_id: Option<i32>,
+    #[serde(default)]
+    headcount: bool,
+    #[serde(default)]
+    flights: Vec<i32>,
+    #[serde(default)]
+    staff: Vec<i32>,
+}
+
+pub(crate) const DRAFT: &str = "draft";
+pub(crate) const IN_REVIEW: &str = "in_review";
+pub(crate) const CHANGES_REQUESTED: &str = "changes_requested";
+pub(crate) const REJECTED: &str = "rejected";
+pub(crate) const APPROVED: &str = "approved";
+
+fn status(e: DbErr) -> StatusCode {
+    match e.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => StatusCode::CONFLICT,
+        Some(SqlErr::ForeignKeyConstraintViolation(_)) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Only drafts and schedules sent back for changes can be edited. Anything in
+/// review or approved is locked and has to be revised into a new draft.
+pub(crate) async fn editable(db: &DbConn, schedule_id: i32) -> Result<(), StatusCode> {
+    match Schedule::find_by_id(schedule_id).one(db).await {
+        Ok(Some(schedule)) if schedule.status == DRAFT || schedule.status == CHANGES_REQUESTED => {
+            Ok(())
+        }
+        Ok(Some(_)) => Err(StatusCode::CONFLICT),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn day_editable(db: &DbConn, day_id: i32) -> Result<entity::schedule_day::Model, StatusCode> {
+    let day = match ScheduleDay::find_by_id(day_id).one(db).await {
+        Ok(Some(day)) => day,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    editable(db, day.schedule_id).await?;
+    Ok(day)
+}
+
+async fn block_editable(
+    db: &DbConn,
+    block_id: i32,
+) -> Result<entity::schedule_day::Model, StatusCode> {
+    match TimeBlock::find_by_id(block_id).one(db).await {
+        Ok(Some(block)) => day_editable(db, block.day_id).await,
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn activity_editable(db: &DbConn, activity_id: i32) -> Result<(), StatusCode> {
+    match Activity::find_by_id(activity_id).one(db).await {
+        Ok(Some(activity)) => block_editable(db, activity.block_id).await.map(|_| ()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Blocks from the newest approved version of each schedule, which is what
+/// everyone is actually working from, keyed by id with the schedule each one
+/// belongs to.
+pub(crate) async fn live_blocks(
+    db: &DbConn,
+) -> Result<HashMap<i32, entity::schedule::Model>, DbErr> {
+    let mut latest: HashMap<Uuid, entity::schedule::Model> = HashMap::new();
+    for schedule in Schedule::find()
+        .filter(entity::schedule::Column::Status.eq(APPROVED))
+        .all(db)
+        .await?
+    {
+        match latest.get(&schedule.series_id) {
+            Some(current) if current.version >= schedule.version => {}
+            _ => {
+                latest.insert(schedule.series_id, schedule);
+            }
+        }
+    }
+
+    let schedules: HashMap<i32, entity::schedule::Model> =
+        latest.into_values().map(|s| (s.id, s)).collect();
+
+    let days: HashMap<i32, i32> = ScheduleDay::find()
+        .filter(entity::schedule_day::Column::ScheduleId.is_in(schedules.keys().copied()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|d| (d.id, d.schedule_id))
+        .collect();
+
+    Ok(TimeBlock::find()
+        .filter(entity::time_block::Column::DayId.is_in(days.keys().copied()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|b| (b.id, schedules[&days[&b.day_id]].clone()))
+        .collect())
+}
+
+#[derive(Serialize)]
+pub(crate) struct BlockAccountability {
+    expected: usize,
+    accounted: usize,
+    headcounts: Vec<Accountability>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ScheduleBlock {
+    #[serde(flatten)]
+    block: entity::time_block::Model,
+    activities: Vec<entity::activity::ModelEx>,
+    accountability: Option<BlockAccountability>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ScheduleDayView {
+    #[serde(flatten)]
+    day: entity::schedule_day::Model,
+    blocks: Vec<ScheduleBlock>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ScheduleView {
+    #[serde(flatten)]
+    schedule: entity::schedule::Model,
+    days: Vec<ScheduleDayView>,
+}
+
+/// The whole schedule laid out day, block, activity, with each activity's
+/// location, flights and staff filled in, and how far along the headcounts
+/// are for blocks that have them.
+pub(crate) async fn load_schedule(db: &DbConn, id: i32) -> Result<Option<ScheduleView>, DbErr> {
+    let Some(schedule) = Schedule::find_by_id(id).one(db).await? else {
+        return Ok(None);
+    };
+
+    let days = ScheduleDay::find()
+        .filter(entity::schedule_day::Column::ScheduleId.eq(id))
+        .order_by_asc(entity::schedule_day::Column::Date)
+        .all(db)
+        .await?;
+
+    let blocks = TimeBlock::find()
+        .filter(entity::time_block::Column::DayId.is_in(days.iter().map(|d| d.id)))
+        .order_by_asc(entity::time_block::Column::StartsAt)
+        .all(db)
+        .await?;
+
+    let mut activities: HashMap<i32, Vec<entity::activity::ModelEx>> = HashMap::new();
+    for activity in Activity::load()
+        .filter(entity::activity::Column::BlockId.is_in(blocks.iter().map(|b| b.id)))
+        .with(Location)
+        .with(Flight)
+        .with(Attendee)
+        .order_by_asc(entity::activity::Column::Id)
+        .all(db)
+        .await?
+    {
+        activities
+            .entry(activity.block_id)
+            .or_default()
+            .push(activity);
+    }
+
+    let headcounts = Headcount::find()
+        .filter(
+            entity::headcount::Column::ActivityUid.is_in(
+                activities
+                    .values()
+                    .flatten()
+                    .filter(|a| a.headcount)
+                    .map(|a| a.uid),
+            ),
+        )
+        .all(db)
+        .await?;
+
+    let mut counts: HashMap<Uuid, Accountability> = accountability(db, &headcounts)
+        .await?
+        .into_iter()
+        .filter_map(|a| a.activity_uid.map(|uid| (uid, a)))
+        .collect();
+
+    let mut by_day: HashMap<i32, Vec<ScheduleBlock>> = HashMap::new();
+    for block in blocks {
+        let activities = activities.remove(&block.id).unwrap_or_default();
+
+        let headcounts: Vec<Accountability> = activities
+            .iter()
+            .filter(|a| a.headcount)
+            .filter_map(|a| counts.remove(&a.uid))
+            .collect();
+
+        let accountability = (!headcounts.is_empty()).then(|| BlockAccountability {
+            expected: headcounts.iter().map(|h| h.expected).sum(),
+            accounted: headcounts.iter().map(|h| h.accounted).sum(),
+            headcounts,
+        });
+
+        by_day.entry(block.day_id).or_default().push(ScheduleBlock {
+            activities,
+            accountability,
+            block,
+        });
+    }
+
+    Ok(Some(ScheduleView {
+        schedule,
+        days: days
+            .into_iter()
+            .map(|day| ScheduleDayView {
+                blocks: by_day.remove(&day.id).unwrap_or_default(),
+                day,
+            })
+            .collect(),
+    }))
+}
+
+pub(crate) async fn get_all_schedules(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<entity::schedule::Model>>, StatusCode> {
+    let mut query = Schedule::find()
+        .order_by_desc(entity::schedule::Column::CreatedAt)
+        .order_by_desc(entity::schedule::Column::Version);
+
+    if let Some(status) = params.get("status") {
+        query = query.filter(entity::schedule::Column::Status.eq(status));
+    }
+
+    if let Some(s) = params.get("seriesId") {
+        let series: Uuid = s.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+        query = query.filter(entity::schedule::Column::SeriesId.eq(series));
+    }
+
+    match query.all(&state.db).await {
+        Ok(schedules) => Ok(Json(schedules)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn create_schedule(
+    State(state): State<Arc<AppState>>,
+    Json(data): Json<ScheduleParameters>,
+) -> Result<Json<entity::schedule::Model>, StatusCode> {
+    if data.name.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let model = entity::schedule::ActiveModel {
+        id: NotSet,
+        name: Set(data.name.trim().to_string()),
+        created_at: Set(Local::now().naive_local()),
+        series_id: Set(Uuid::new_v4()),
+        version: Set(1),
+        status: Set(DRAFT.to_string()),
+        stage: Set(0),
+        previous_version_id: Set(None),
+        published_at: Set(None),
+    };
+
+    model.insert(&state.db).await.map(Json).map_err(status)
+}
+
+pub(crate) async fn get_schedule(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<ScheduleView>, StatusCode> {
+    match load_schedule(&state.db, id).await {
+        Ok(Some(schedule)) => Ok(Json(schedule)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn update_schedule(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<ScheduleParameters>,
+) -> impl IntoResponse {
+    if data.name.trim().is_empty() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    if let Err(status) = editable(&state.db, id).await {
+        return status;
+    }
+
+    let model = entity::schedule::ActiveModel {
+        id: Unchanged(id),
+        name: Set(data.name.trim().to_string()),
+        ..Default::default()
+    };
+
+    match model.update(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(e) => status(e),
+    }
+}
+
+pub(crate) async fn delete_schedule(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    if let Err(status) = editable(&state.db, id).await {
+        return status;
+    }
+
+    match Schedule::delete_by_id(id).exec(&state.db).await {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn add_schedule_day(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<ScheduleDayParameters>,
+) -> Result<Json<entity::schedule_day::Model>, StatusCode> {
+    editable(&state.db, id).await?;
+
+    let model = entity::schedule_day::ActiveModel {
+        id: NotSet,
+        schedule_id: Set(id),
+        date: Set(data.date),
+        uniform: Set(uniform(data.uniform)),
+    };
+
+    model.insert(&state.db).await.map(Json).map_err(status)
+}
+
+fn uniform(uniform: Option<String>) -> Option<String> {
+    uniform
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty())
+}
+
+/// Sets the uniform of the day, which goes out on the plan of the day.
+pub(crate) async fn update_schedule_day(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<UniformParameters>,
+) -> impl IntoResponse {
+    if let Err(status) = day_editable(&state.db, id).await {
+        return status;
+    }
+
+    let model = entity::schedule_day::ActiveModel {
+        id: Unchanged(id),
+        uniform: Set(uniform(data.uniform)),
+        ..Default::default()
+    };
+
+    match model.update(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(e) => status(e),
+    }
+}
+
+pub(crate) async fn delete_schedule_day(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    if let Err(status) = day_editable(&state.db, id).await {
+        return status;
+    }
+
+    match ScheduleDay::delete_by_id(id).exec(&state.db).await {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Blocks have to start on the day they're filed under, though they may run
+/// past midnight.
+fn check_block(day: &entity::schedule_day::Model, data: &TimeBlockParameters) -> bool {
+    !data.name.trim().is_empty()
+        && data.ends_at > data.starts_at
+        && data.starts_at.date() == day.date
+}
+
+pub(crate) async fn add_time_block(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<TimeBlockParameters>,
+) -> Result<Json<entity::time_block::Model>, StatusCode> {
+    let day = day_editable(&state.db, id).await?;
+
+    if !check_block(&day, &data) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let model = entity::time_block::ActiveModel {
+        id: NotSet,
+        day_id: Set(day.id),
+        name: Set(data.name.trim().to_string()),
+        starts_at: Set(data.starts_at),
+        ends_at: Set(data.ends_at),
+    };
+
+    model.insert(&state.db).await.map(Json).map_err(status)
+}
+
+pub(crate) async fn update_time_block(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<TimeBlockParameters>,
+) -> impl IntoResponse {
+    let day = match block_editable(&state.db, id).await {
+        Ok(day) => day,
+        Err(status) => return status,
+    };
+
+    if !check_block(&day, &data) {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let model = entity::time_block::ActiveModel {
+        id: Unchanged(id),
+        day_id: NotSet,
+        name: Set(data.name.trim().to_string()),
+        starts_at: Set(data.starts_at),
+        ends_at: Set(data.ends_at),
+    };
+
+    match model.update(&state.db).await {
+        Ok(_) => StatusCode::OK,
+        Err(e) => status(e),
+    }
+}
+
+pub(crate) async fn delete_time_block(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    if let Err(status) = block_editable(&state.db, id).await {
+        return status;
+    }
+
+    match TimeBlock::delete_by_id(id).exec(&state.db).await {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Replaces the flights and staff on an activity.
+async fn assign<C: ConnectionTrait>(
+    db: &C,
+    activity_id: i32,
+    data: &ActivityParameters,
+) -> Result<(), DbErr> {
+    ActivityFlight::delete_many()
+        .filter(entity::activity_flight::Column::ActivityId.eq(activity_id))
+        .exec(db)
+        .await?;
+
+    ActivityStaff::delete_many()
+        .filter(entity::activity_staff::Column::ActivityId.eq(activity_id))
+        .exec(db)
+        .await?;
+
+    let mut flights = data.flights.clone();
+    flights.sort();
+    flights.dedup();
+
+    if !flights.is_empty() {
+        ActivityFlight::insert_many(flights.into_iter().map(|flight_id| {
+            entity::activity_flight::ActiveModel {
+                id: NotSet,
+                activity_id: Set(activity_id),
+                flight_id: Set(flight_id),
+            }
+        }))
+        .exec(db)
+        .await?;
+    }
+
+    let mut staff = data.staff.clone();
+    staff.sort();
+    staff.dedup();
+
+    if !staff.is_empty() {
+        ActivityStaff::insert_many(staff.into_iter().map(|capid| {
+            entity::activity_staff::ActiveModel {
+                id: NotSet,
+                activity_id: Set(activity_id),
+                capid: Set(capid),
+            }
+        }))
+        .exec(db)
+        .await?;
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn add_activity(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<ActivityParameters>,
+) -> Result<Json<entity::activity::Model>, StatusCode> {
+    if data.name.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    block_editable(&state.db, id).await?;
+
+    let txn = state
+        .db
+        .begin()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let activity = entity::activity::ActiveModel {
+        id: NotSet,
+        block_id: Set(id),
+        uid: Set(Uuid::new_v4()),
+        name: Set(data.name.trim().to_string()),
+        description: Set(data.description.clone()),
+        location_id: Set(data.location_id),
+        headcount: Set(data.headcount),
+    }
+    .insert(&txn)
+    .await
+    .map_err(status)?;
+
+    assign(&txn, activity.id, &data).await.map_err(status)?;
+
+    match txn.commit().await {
+        Ok(_) => Ok(Json(activity)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn get_activity(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<entity::activity::ModelEx>, StatusCode> {
+    match Activity::load()
+        .filter_by_id(id)
+        .with(TimeBlock)
+        .with(Location)
+        .with(Flight)
+        .with(Attendee)
+        .one(&state.db)
+        .await
+    {
+        Ok(Some(activity)) => Ok(Json(activity)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(crate) async fn update_activity(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(data): Json<ActivityParameters>,
+) -> impl IntoResponse {
+    if data.name.trim().is_empty() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    if let Err(status) = activity_editable(&state.db, id).await {
+        return status;
+    }
+
+    let txn = match state.db.begin().await {
+        Ok(txn) => txn,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    let model = entity::activity::ActiveModel {
+        id: Unchanged(id),
+        block_id: NotSet,
+        uid: NotSet,
+        name: Set(data.name.trim().to_string()),
+        description: Set(data.description.clone()),
+        location_id: Set(data.location_id),
+        headcount: Set(data.headcount),
+    };
+
+    if let Err(e) = model.update(&txn).await {
+        return status(e);
+    }
+
+    if let Err(e) = assign(&txn, id, &data).await {
+        return status(e);
+    }
+
+    match txn.commit().await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) async fn delete_activity(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    if let Err(status) = activity_editable(&state.db, id).await {
+        return status;
+    }
+
+    match Activity::delete_by_id(id).exec(&state.db).await {
+        Ok(res) if res.rows_affected == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct NowNext {
+    at: NaiveDateTime,
+    now: Vec<entity::activity::ModelEx>,
+    next: Vec<entity::activity::ModelEx>,
+}
+
+/// Splits the given activities into what's running at `at` and everything in
+/// the next block to start after it, going only by approved schedules.
+async fn now_next(db: &DbConn, ids: Vec<i32>, at: NaiveDateTime) -> Result<NowNext, DbErr> {
+    let activities = Activity::load()
+        .filter(entity::activity::Column::Id.is_in(ids))
+        .filter(entity::activity::Column::BlockId.is_in(live_blocks(db).await?.into_keys()))
+        .with(TimeBlock)
+        .with(Location)
+        .with(Flight)
+        .with(Attendee)
+        .all(db)
+        .await?;
+
+    let mut now = vec![];
+    let mut upcoming = vec![];
+    for activity in activities {
+        let Some(block) = activity.block.as_ref() else {
+            continue;
+        };
+
+        if block.starts_at <= at && block.ends_at > at {
+            now.push(activity);
+        } else if block.starts_at > at {
+            upcoming.push((block.starts_at, activity));
+        }
+    }
+
+    let first = upcoming.iter().map(|(starts_at, _)| *starts_at).min();
+
+    Ok(NowNext {
+        at,
+        now,
+        next: upcoming
+            .into_iter()
+            .filter(|(starts_at, _)| Some(*starts_at) == first)
+            .map(|(_, activity)| activity)
+            .collect(),
+    })
+}
+
+fn at(params: &HashMap<String, String>) -> Result<NaiveDateTime, StatusCode> {
+    match params.get("at") {
+        Some(s) => s.parse().map_err(|_| StatusCode::BAD_REQUEST),
+        None => Ok(Local::now().naive_local()),
+    }
+}
+
+/// Every activity a flight is assigned to, in any schedule.
+pub(crate) async fn flight_activities(db: &DbConn, flight_id: i32) -> Result<Vec<i32>, DbErr> {
+    Ok(ActivityFlight::find()
+        .filter(entity::activity_flight::Column::FlightId.eq(flight_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|a| a.activity_id)
+        .collect())
+}
+
+/// Every activity an attendee is in, through their flight and anything
+/// they're staffing.
+pub(crate) async fn attendee_activities(db: &DbConn, capid: i32) -> Result<Vec<i32>, DbErr> {
+    let flights: Vec<i32> = FlightMember::find()
+        .filter(entity::flight_member::Column::Capid.eq(capid))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|m| m.flight_id)
+        .collect();
+
+    let mut ids: Vec<i32> = ActivityFlight::find()
+        .filter(entity::activity_flight::Column::FlightId.is_in(flights))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|a| a.activity_id)
+        .collect();
+
+    ids.extend(
+        ActivityStaff::find()
+            .filter(entity::activity_staff::Column::Capid.eq(capid))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|a| a.activity_id),
+    );
+    ids.sort();
+    ids.dedup();
+
+    Ok(ids)
+}
+
+/// What a flight is doing now and next, `?at=` to look from another time.
+pub(crate) async fn get_flight_now_next(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<NowNext>, StatusCode> {
+    let at = at(&params)?;
+
+    let ids = flight_activities(&state.db, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    now_next(&state.db, ids, at)
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// What an attendee is doing now and next.
+pub(crate) async fn get_attendee_now_next(
+    State(state): State<Arc<AppState>>,
+    Path(capid): Path<i32>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<NowNext>, StatusCode> {
+    let at = at(&params)?;
+
+    let ids = attendee_activities(&state.db, capid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    now_next(&state.db, ids, at)
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
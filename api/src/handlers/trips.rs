@@ -0,0 +1,286 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use chrono::{Local, Months, NaiveDate, NaiveDateTime};
+use entity::prelude::{Attendee, Vehicle, VehicleTrip};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, ConnectionTrait, DbConn, EntityLoaderTrait,
+    EntityTrait, IntoActiveModel, QueryFilter, QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::AppState;
+use crate::export;
+
+#[derive(Deserialize)]
+pub(crate) struct Departure {
+    purpose: String,
+    destination: String,
+    odometer: i32,
+    pub(crate) return_date: Option<NaiveDate>,
+}
+
+pub(crate) struct Arrival {
+    odometer: Option<i32>,
+    fuel_added: Option<f64>,
+}
+
+impl Arrival {
+    pub(crate) fn from_params(params: &HashMap<String, String>) -> Result<Self, StatusCode> {
+        let odometer = match params.get("odometer") {
+            Some(s) => Some(s.parse().map_err(|_| StatusCode::BAD_REQUEST)?),
+            None => None,
+        };
+
+        let fuel_added = match params.get("fuelAdded") {
+            Some(s) => Some(s.parse().map_err(|_| StatusCode::BAD_REQUEST)?),
+            None => None,
+        };
+
+        Ok(Arrival {
+            odometer,
+            fuel_added,
+        })
+    }
+}
+
+pub(crate) async fn open_trip<C: ConnectionTrait>(
+    db: &C,
+    vehicle_id: i32,
+    driver_capid: i32,
+    departure: Departure,
+) -> Result<entity::vehicle_trip::Model, StatusCode> {
+    let open = VehicleTrip::find()
+        .filter(entity::vehicle_trip::Column::VehicleId.eq(vehicle_id))
+        .filter(entity::vehicle_trip::Column::ReturnedAt.is_null())
+        .one(db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // Handing the keys straight to another driver ends the previous trip
+    // where the new one starts, so the odometer can't have gone backwards
+    if let Some(trip) = open {
+        if departure.odometer < trip.start_odometer {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+
+        let mut model = trip.into_active_model();
+        model.end_odometer = Set(Some(departure.odometer));
+        model.returned_at = Set(Some(Local::now().naive_local()));
+
+        model
+            .update(db)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    }
+
+    entity::vehicle_trip::ActiveModel {
+        id: NotSet,
+        vehicle_id: Set(vehicle_id),
+        driver_capid: Set(driver_capid),
+        purpose: Set(departure.purpose),
+        destination: Set(departure.destination),
+        start_odometer: Set(departure.odometer),
+        end_odometer: Set(None),
+        departed_at: Set(Local::now().naive_local()),
+        returned_at: Set(None),
+        fuel_added: Set(None),
+    }
+    .insert(db)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+pub(crate) async fn close_trip<C: ConnectionTrait>(
+    db: &C,
+    vehicle_id: i32,
+    arrival: Arrival,
+) -> Result<(), StatusCode> {
+    let trip = match VehicleTrip::find()
+        .filter(entity::vehicle_trip::Column::VehicleId.eq(vehicle_id))
+        .filter(entity::vehicle_trip::Column::ReturnedAt.is_null())
+        .one(db)
+        .await
+    {
+        Ok(Some(trip)) => trip,
+        Ok(None) => return Ok(()),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let odometer = match arrival.odometer {
+        Some(odometer) if odometer >= trip.start_odometer => odometer,
+        _ => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    let mut model = trip.into_active_model();
+    model.end_odometer = Set(Some(odometer));
+    model.returned_at = Set(Some(Local::now().naive_local()));
+    model.fuel_added = Set(arrival.fuel_added);
+
+    match model.update(db).await {
+        Ok(_) => Ok(()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+fn month(
+    params: &HashMap<String, String>,
+) -> Result<Option<(NaiveDateTime, NaiveDateTime)>, StatusCode> {
+    let month = match params.get("month") {
+        Some(month) => month,
+        None => return Ok(None),
+    };
+
+    let start = NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d")
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let end = start
+        .checked_add_months(Months::new(1))
+        .ok_or(StatusCode::BAD_REQUEST)?;
+
+    Ok(Some((
+        start.and_hms_opt(0, 0, 0).unwrap(),
+        end.and_hms_opt(0, 0, 0).unwrap(),
+    )))
+}
+
+async fn find_trips(
+    db: &DbConn,
+    params: &HashMap<String, String>,
+) -> Result<Vec<entity::vehicle_trip::ModelEx>, StatusCode> {
+    let mut query = VehicleTrip::load()
+        .with(Vehicle)
+        .with(Attendee)
+        .order_by_asc(entity::vehicle_trip::Column::DepartedAt);
+
+    if let Some(s) = params.get("vehicleId") {
+        let id: i32 = s.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+        query = query.filter(entity::vehicle_trip::Column::VehicleId.eq(id));
+    }
+
+    if let Some((start, end)) = month(params)? {
+        query = query
+            .filter(entity::vehicle_trip::Column::DepartedAt.gte(start))
+            .filter(entity::vehicle_trip::Column::DepartedAt.lt(end));
+    }
+
+    query
+        .all(db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+pub(crate) async fn get_all_trips(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<entity::vehicle_trip::ModelEx>>, StatusCode> {
+    find_trips(&state.db, &params).await.map(Json)
+}
+
+pub(crate) async fn get_trip(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<entity::vehicle_trip::ModelEx>, StatusCode> {
+    match VehicleTrip::load()
+        .filter_by_id(id)
+        .with(Vehicle)
+        .with(Attendee)
+        .one(&state.db)
+        .await
+    {
+        Ok(Some(trip)) => Ok(Json(trip)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+#[derive(Default, Serialize)]
+pub(crate) struct Mileage {
+    vehicle_id: i32,
+    trips: usize,
+    miles: i32,
+    fuel_added: f64,
+}
+
+pub(crate) async fn get_mileage(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<Mileage>>, StatusCode> {
+    let trips = find_trips(&state.db, &params).await?;
+
+    let mut totals: BTreeMap<i32, Mileage> = BTreeMap::new();
+    for trip in trips {
+        let total = totals.entry(trip.vehicle_id).or_insert_with(|| Mileage {
+            vehicle_id: trip.vehicle_id,
+            ..Default::default()
+        });
+
+        total.trips += 1;
+        if let Some(end) = trip.end_odometer {
+            total.miles += end - trip.start_odometer;
+        }
+        total.fuel_added += trip.fuel_added.unwrap_or_default();
+    }
+
+    Ok(Json(totals.into_values().collect()))
+}
+
+pub(crate) async fn export_trips(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let trips = find_trips(&state.db, &params).await?;
+
+    let rows: Vec<Vec<String>> = trips
+        .into_iter()
+        .map(|trip| {
+            let driver = match trip.driver.as_ref() {
+                Some(a) => format!("{} {} {}", a.rank, a.first_name, a.last_name),
+                None => trip.driver_capid.to_string(),
+            };
+
+            vec![
+                trip.vehicle_id.to_string(),
+                trip.departed_at.format("%Y-%m-%d %H:%M").to_string(),
+                trip.returned_at
+                    .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default(),
+                driver,
+                trip.driver_capid.to_string(),
+                trip.purpose,
+                trip.destination,
+                trip.start_odometer.to_string(),
+                trip.end_odometer.map(|o| o.to_string()).unwrap_or_default(),
+                trip.end_odometer
+                    .map(|o| (o - trip.start_odometer).to_string())
+                    .unwrap_or_default(),
+                trip.fuel_added.map(|f| f.to_string()).unwrap_or_default(),
+            ]
+        })
+        .collect();
+
+    let file_name = match params.get("month") {
+        Some(month) => format!("vehicle-trips-{month}.csv"),
+        None => "vehicle-trips.csv".to_string(),
+    };
+
+    export::csv(
+        &file_name,
+        &[
+            "Vehicle",
+            "Departed",
+            "Returned",
+            "Driver",
+            "CAPID",
+            "Purpose",
+            "Destination",
+            "Start Odometer",
+            "End Odometer",
+            "Miles",
+            "Fuel Added",
+        ],
+        &rows,
+    )
+}
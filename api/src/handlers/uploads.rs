@@ -1,6 +1,9 @@
+use std::path::PathBuf;
+use std::time::SystemTime;
+
 use axum::Json;
 use axum::body::Body;
-use axum::extract::Query;
+use axum::extract::{Multipart, Query};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use sanitize_filename::sanitize;
@@ -50,3 +53,63 @@ pub(crate) async fn download_file(Query(params): Query<DownloadParams>) -> impl
 
     Ok((StatusCode::OK, body))
 }
+
+/// A photo saved to the uploads folder from a multipart `photo` field.
+pub(crate) struct Photo {
+    pub(crate) file_name: String,
+    pub(crate) content_type: String,
+}
+
+/// Saves each PNG or JPEG `photo` field, naming the files with `prefix` so
+/// they can be traced back to the record they're attached to. Sending no
+/// photos, or anything that isn't one, is a 400.
+pub(crate) async fn save_photos(
+    multipart: &mut Multipart,
+    prefix: &str,
+) -> Result<Vec<Photo>, StatusCode> {
+    let mut photos = vec![];
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|_| StatusCode::BAD_REQUEST)?
+    {
+        if field.name() != Some("photo") {
+            continue;
+        }
+
+        let content_type = match field.content_type() {
+            Some(t @ ("image/png" | "image/jpeg")) => t.to_string(),
+            _ => return Err(StatusCode::BAD_REQUEST),
+        };
+        let original = field.file_name().unwrap_or("photo").to_string();
+
+        let data = field.bytes().await.map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        let file_name = format!(
+            "{}_{prefix}_{}",
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_millis(),
+            sanitize(original)
+        );
+        let mut path = PathBuf::from(dotenvy::var("UPLOADS_PATH").unwrap());
+        path.push(&file_name);
+
+        tokio::fs::write(&path, data)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        photos.push(Photo {
+            file_name,
+            content_type,
+        });
+    }
+
+    if photos.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    Ok(photos)
+}
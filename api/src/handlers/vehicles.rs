@@ -1,17 +1,79 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use axum::extract::{Path, Query, State};
+use axum::extract::{Multipart, Query, State};
 use axum::http::StatusCode;
-use axum::response::{IntoResponse, Json};
-use chrono::Local;
+use axum::response::{IntoResponse, Json, Response};
+use chrono::{Local, NaiveDateTime, TimeDelta};
 use entity::prelude::{Attendee, Vehicle, VehicleInspection, VehicleType};
 use sea_orm::{
-    ActiveModelTrait, ActiveValue::*, DbErr, EntityLoaderTrait, EntityTrait, IntoActiveModel,
+    ActiveModelTrait, ActiveValue::*, ColumnTrait, DbConn, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, SqlErr, TransactionTrait,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::AppState;
+use crate::handlers::assets::tag_in_use;
+use crate::handlers::drivers::{self, Override};
+use crate::handlers::maintenance::{ground_vehicle, service_holds};
+use crate::handlers::receipts::{self, Custody, Item};
+use crate::handlers::trips::{Arrival, Departure, close_trip, open_trip};
+use crate::import::{ImportReport, Row, read_rows, save_upload};
+
+#[derive(Serialize)]
+pub(crate) struct InspectionStatus {
+    inspection_id: i32,
+    signed_at: NaiveDateTime,
+    expires_at: NaiveDateTime,
+    mission_ready: bool,
+    ic_override: bool,
+    current: bool,
+}
+
+impl InspectionStatus {
+    fn new(inspection: &entity::vehicle_inspection::Model, valid_for: TimeDelta) -> Option<Self> {
+        let signed_at = inspection.signed_at?;
+        let expires_at = signed_at + valid_for;
+        let mission_ready = inspection.is_mission_ready == Some(true);
+        let ic_override = inspection.ic_signed_at.is_some();
+
+        Some(InspectionStatus {
+            inspection_id: inspection.id,
+            signed_at,
+            expires_at,
+            mission_ready,
+            ic_override,
+            current: expires_at > Local::now().naive_local() && (mission_ready || ic_override),
+        })
+    }
+}
+
+async fn latest_inspection(
+    db: &DbConn,
+    vehicle_id: Option<i32>,
+) -> Result<HashMap<i32, entity::vehicle_inspection::Model>, DbErr> {
+    let mut query = VehicleInspection::find()
+        .filter(entity::vehicle_inspection::Column::SignedAt.is_not_null())
+        .order_by_desc(entity::vehicle_inspection::Column::SignedAt);
+
+    if let Some(id) = vehicle_id {
+        query = query.filter(entity::vehicle_inspection::Column::VehicleId.eq(id));
+    }
+
+    let mut latest = HashMap::new();
+    for inspection in query.all(db).await? {
+        latest.entry(inspection.vehicle_id).or_insert(inspection);
+    }
+
+    Ok(latest)
+}
+
+#[derive(Serialize)]
+pub(crate) struct VehicleListing {
+    #[serde(flatten)]
+    vehicle: entity::vehicle::ModelEx,
+    inspection: Option<InspectionStatus>,
+}
 
 pub(crate) async fn get_all_vehicles(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     let vehicles: Vec<entity::vehicle::ModelEx> = Vehicle::load()
@@ -21,6 +83,20 @@ pub(crate) async fn get_all_vehicles(State(state): State<Arc<AppState>>) -> impl
         .await
         .unwrap();
 
+    let inspections = latest_inspection(&state.db, None)
+        .await
+        .expect("Could not get inspections");
+
+    let vehicles: Vec<VehicleListing> = vehicles
+        .into_iter()
+        .map(|vehicle| VehicleListing {
+            inspection: inspections
+                .get(&vehicle.id)
+                .and_then(|i| InspectionStatus::new(i, state.config.inspection.valid_for)),
+            vehicle,
+        })
+        .collect();
+
     Json(vehicles)
 }
 
@@ -28,6 +104,14 @@ pub(crate) async fn create_new_vehicle(
     State(state): State<Arc<AppState>>,
     Json(vehicle): Json<entity::vehicle::Model>,
 ) -> impl IntoResponse {
+    if let Some(tag) = &vehicle.property_tag_number {
+        match tag_in_use(&state.db, tag).await {
+            Ok(false) => {}
+            Ok(true) => return StatusCode::CONFLICT,
+            Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
     let active_model = entity::vehicle::ActiveModel {
         id: Set(vehicle.id),
         type_id: Set(vehicle.type_id),
@@ -35,11 +119,15 @@ pub(crate) async fn create_new_vehicle(
         owned_by_unit: Set(vehicle.owned_by_unit),
         issued_to: Set(None),
         in_service: Set(vehicle.in_service),
+        property_tag_number: Set(vehicle.property_tag_number),
     };
 
     match active_model.insert(&state.db).await {
         Ok(_) => StatusCode::OK,
         Err(DbErr::RecordNotInserted) => StatusCode::BAD_REQUEST,
+        Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+            StatusCode::CONFLICT
+        }
         Err(e) => {
             println!("{e:#?}");
             StatusCode::INTERNAL_SERVER_ERROR
@@ -47,6 +135,95 @@ pub(crate) async fn create_new_vehicle(
     }
 }
 
+pub(crate) async fn create_vehicle_bulk(
+    State(state): State<Arc<AppState>>,
+    mut multipart: Multipart,
+) -> Result<Json<ImportReport>, StatusCode> {
+    let upload = save_upload(&mut multipart).await?;
+    let rows = read_rows(&upload).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let mut types: HashMap<(String, String), i32> = VehicleType::find()
+        .all(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .into_iter()
+        .map(|t| ((t.make.to_lowercase(), t.model.to_lowercase()), t.id))
+        .collect();
+
+    let mut report = ImportReport::default();
+
+    for row in rows {
+        let result = import_vehicle(&state.db, &row, &mut types).await;
+        report.record(row.number, result);
+    }
+
+    Ok(Json(report))
+}
+
+async fn import_vehicle(
+    db: &DbConn,
+    row: &Row,
+    types: &mut HashMap<(String, String), i32>,
+) -> Result<(), String> {
+    let id: i32 = row.parse(&["vehicle id", "id", "vehicle number"])?;
+    let year: i32 = row.parse(&["year"])?;
+    let make = row.require(&["make"])?;
+    let model = row.require(&["model"])?;
+    let owned_by_unit = row.require(&["owned by unit", "unit"])?;
+    let in_service = row.flag(&["in service"], true)?;
+    let property_tag_number = row
+        .get(&["property tag", "property tag number", "tag"])
+        .map(str::to_string);
+
+    if let Some(tag) = &property_tag_number
+        && tag_in_use(db, tag).await.map_err(|e| e.to_string())?
+    {
+        return Err(format!("Property tag {tag} is already in use"));
+    }
+
+    let txn = db.begin().await.map_err(|e| e.to_string())?;
+
+    let key = (make.to_lowercase(), model.to_lowercase());
+    let (type_id, new_type) = match types.get(&key) {
+        Some(id) => (*id, false),
+        None => {
+            let capacity: i32 = row.parse(&["capacity"])?;
+            let vehicle_type = entity::vehicle_type::ActiveModel {
+                id: NotSet,
+                make: Set(make.to_string()),
+                model: Set(model.to_string()),
+                capacity: Set(capacity),
+            }
+            .insert(&txn)
+            .await
+            .map_err(|e| format!("Could not create vehicle type {make} {model}: {e}"))?;
+
+            (vehicle_type.id, true)
+        }
+    };
+
+    entity::vehicle::ActiveModel {
+        id: Set(id),
+        type_id: Set(type_id),
+        year: Set(year),
+        owned_by_unit: Set(owned_by_unit.to_string()),
+        issued_to: Set(None),
+        in_service: Set(in_service),
+        property_tag_number: Set(property_tag_number),
+    }
+    .insert(&txn)
+    .await
+    .map_err(|e| format!("Could not save vehicle {id}: {e}"))?;
+
+    txn.commit().await.map_err(|e| e.to_string())?;
+
+    if new_type {
+        types.insert(key, type_id);
+    }
+
+    Ok(())
+}
+
 pub(crate) async fn delete_vehicle(
     State(state): State<Arc<AppState>>,
     Query(params): Query<HashMap<String, String>>,
@@ -67,6 +244,10 @@ pub(crate) async fn delete_vehicle(
     match model.delete(&state.db).await {
         Ok(_) => StatusCode::OK,
         Err(DbErr::RecordNotFound(_)) => StatusCode::BAD_REQUEST,
+        // A vehicle with receipts or logged trips is kept for the record
+        Err(e) if matches!(e.sql_err(), Some(SqlErr::ForeignKeyConstraintViolation(_))) => {
+            StatusCode::CONFLICT
+        }
         _ => StatusCode::INTERNAL_SERVER_ERROR,
     }
 }
@@ -125,33 +306,153 @@ pub(crate) async fn delete_vehicle_type(
 #[derive(Deserialize)]
 pub(crate) struct IssueVehicleParameters {
     vehicle_id: i32,
-    capid: i32,
+    #[serde(flatten)]
+    custody: Custody,
+    #[serde(flatten)]
+    departure: Departure,
+    #[serde(flatten)]
+    approval: Option<Override>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct IssuedVehicle {
+    receipt: entity::hand_receipt::Model,
+    trip: entity::vehicle_trip::Model,
 }
 
 pub(crate) async fn issue_vehicle(
     State(state): State<Arc<AppState>>,
     Json(data): Json<IssueVehicleParameters>,
 ) -> impl IntoResponse {
-    let model = match Vehicle::find_by_id(data.vehicle_id).one(&state.db).await {
+    issue(
+        &state,
+        data.vehicle_id,
+        data.custody,
+        data.departure,
+        data.approval,
+    )
+    .await
+}
+
+pub(crate) async fn issue(
+    state: &AppState,
+    vehicle_id: i32,
+    custody: Custody,
+    departure: Departure,
+    approval: Option<Override>,
+) -> Result<Json<IssuedVehicle>, Response> {
+    let db = &state.db;
+
+    let model = match Vehicle::find_by_id(vehicle_id).one(db).await {
         Ok(opt) => match opt {
             Some(model) => model,
-            None => return StatusCode::BAD_REQUEST,
+            None => return Err(StatusCode::BAD_REQUEST.into_response()),
         },
-        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response()),
     };
 
+    if !model.in_service {
+        let mut reasons = service_holds(db, vehicle_id)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+        if reasons.is_empty() {
+            reasons.push("Vehicle is out of service".to_string());
+        }
+        return Err(drivers::refuse(reasons));
+    }
+
+    let inspection = latest_inspection(db, Some(vehicle_id))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?
+        .remove(&vehicle_id);
+
+    let status = inspection
+        .as_ref()
+        .and_then(|i| InspectionStatus::new(i, state.config.inspection.valid_for));
+
+    match status {
+        None => {
+            return Err(drivers::refuse(vec![
+                "Vehicle has no signed inspection".to_string(),
+            ]));
+        }
+        Some(status) if !status.current => {
+            let mut reasons = vec![];
+            if status.expires_at <= Local::now().naive_local() {
+                reasons.push(format!(
+                    "Inspection {} expired at {}",
+                    status.inspection_id,
+                    status.expires_at.format("%d %b %Y %H:%M")
+                ));
+            }
+            if !status.mission_ready && !status.ic_override {
+                reasons.push(format!(
+                    "Inspection {} is not mission ready and has no IC override",
+                    status.inspection_id
+                ));
+            }
+            return Err(drivers::refuse(reasons));
+        }
+        Some(_) => {}
+    }
+
+    let trip_date = departure
+        .return_date
+        .unwrap_or_else(|| Local::now().date_naive());
+
+    let driver_override = drivers::check(
+        db,
+        &state.config.driver,
+        vehicle_id,
+        custody.capid,
+        trip_date,
+        approval,
+    )
+    .await?;
+
+    let txn = db
+        .begin()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+
     let active_model = entity::vehicle::ActiveModel {
         id: Unchanged(model.id),
         type_id: Unchanged(model.type_id),
         year: Unchanged(model.year),
         owned_by_unit: Unchanged(model.owned_by_unit),
-        issued_to: Set(Some(data.capid)),
+        issued_to: Set(Some(custody.capid)),
         in_service: Unchanged(model.in_service),
+        property_tag_number: Unchanged(model.property_tag_number),
     };
 
-    match active_model.update(&state.db).await {
-        Ok(_) => StatusCode::OK,
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    if active_model.update(&txn).await.is_err() {
+        return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+    }
+
+    // Reissuing without a turn-in hands custody straight to the new holder
+    receipts::close(&txn, Item::Vehicle(vehicle_id))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+
+    let driver = custody.capid;
+
+    let receipt = receipts::open(&txn, Item::Vehicle(vehicle_id), custody)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+
+    let trip = open_trip(&txn, vehicle_id, driver, departure)
+        .await
+        .map_err(IntoResponse::into_response)?;
+
+    if let Some(log) = driver_override
+        && log.insert(&txn).await.is_err()
+    {
+        return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+    }
+
+    match txn.commit().await {
+        Ok(_) => Ok(Json(IssuedVehicle { receipt, trip })),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR.into_response()),
     }
 }
 
@@ -175,268 +476,78 @@ pub(crate) async fn return_vehicle(
         None => true,
     };
 
-    let model = match Vehicle::find_by_id(id).one(&state.db).await {
-        Ok(opt) => match opt {
-            Some(model) => model,
-            None => return StatusCode::BAD_REQUEST,
-        },
-        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    let arrival = match Arrival::from_params(&params) {
+        Ok(arrival) => arrival,
+        Err(status) => return status,
     };
 
-    let active_model = entity::vehicle::ActiveModel {
-        id: Unchanged(model.id),
-        type_id: Unchanged(model.type_id),
-        year: Unchanged(model.year),
-        owned_by_unit: Unchanged(model.owned_by_unit),
-        issued_to: Set(None),
-        in_service: Set(in_service),
-    };
-
-    match active_model.update(&state.db).await {
-        Ok(_) => StatusCode::OK,
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
-    }
-}
-
-pub(crate) async fn get_all_inspections(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let inspections = VehicleInspection::find()
-        .all(&state.db)
-        .await
-        .expect("Could not get inspections");
+    let problem = params.get("problem").cloned();
 
-    Json(inspections)
+    turn_in(&state.db, id, in_service, problem, arrival).await
 }
 
-#[derive(Deserialize)]
-pub(crate) struct StartInspectionParameters {
+/// Takes a vehicle back. Turning it in serviceable doesn't clear anything
+/// else holding it out; turning it in unserviceable grounds it.
+pub(crate) async fn turn_in(
+    db: &DbConn,
     vehicle_id: i32,
-    capid: i32,
-}
-
-pub(crate) async fn start_inspection(
-    State(state): State<Arc<AppState>>,
-    Json(data): Json<StartInspectionParameters>,
-) -> impl IntoResponse {
-    let active_model = entity::vehicle_inspection::ActiveModel {
-        id: NotSet,
-        started_at: Set(Local::now().naive_local()),
-        vehicle_id: Set(data.vehicle_id),
-        inspector_capid: Set(Some(data.capid)),
-        ..Default::default()
-    };
-
-    match active_model.insert(&state.db).await {
-        Ok(_) => StatusCode::OK,
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
-    }
-}
-
-pub(crate) async fn get_inspection(
-    State(state): State<Arc<AppState>>,
-    Path(id): Path<i32>,
-) -> Result<Json<entity::vehicle_inspection::ModelEx>, StatusCode> {
-    match entity::vehicle_inspection::Entity::load()
-        .filter_by_id(id)
-        .with(Vehicle)
-        .with(Attendee)
-        .one(&state.db)
-        .await
-    {
-        Ok(model) => match model {
-            Some(v) => Ok(Json(v)),
-            None => return Err(StatusCode::NOT_FOUND),
-        },
-        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
-    }
-}
-
-pub(crate) async fn update_inspection(
-    State(state): State<Arc<AppState>>,
-    Json(inspection): Json<entity::vehicle_inspection::Model>,
-) -> impl IntoResponse {
-    let mut model = match entity::vehicle_inspection::Entity::load()
-        .filter_by_id(inspection.id)
-        .one(&state.db)
-        .await
-    {
-        Ok(model) => match model {
-            Some(v) => v.into_active_model(),
+    in_service: bool,
+    problem: Option<String>,
+    arrival: Arrival,
+) -> StatusCode {
+    let model = match Vehicle::find_by_id(vehicle_id).one(db).await {
+        Ok(opt) => match opt {
+            Some(model) => model,
             None => return StatusCode::BAD_REQUEST,
         },
         Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
     };
 
-    model.windows = Set(inspection.windows);
-    model.windows_remarks = Set(inspection.windows_remarks);
-    model.headlights = Set(inspection.headlights);
-    model.headlights_remarks = Set(inspection.headlights_remarks);
-    model.tail_lights = Set(inspection.tail_lights);
-    model.tail_lights_remarks = Set(inspection.tail_lights_remarks);
-    model.brake_lights = Set(inspection.brake_lights);
-    model.brake_lights_remarks = Set(inspection.brake_lights_remarks);
-    model.turn_signals = Set(inspection.turn_signals);
-    model.turn_signals_remarks = Set(inspection.turn_signals_remarks);
-    model.emergency_lights = Set(inspection.emergency_lights);
-    model.emergency_lights_remarks = Set(inspection.emergency_lights_remarks);
-    model.license_plate_light = Set(inspection.license_plate_light);
-    model.license_plate_light_remarks = Set(inspection.license_plate_light_remarks);
-    model.backup_light = Set(inspection.backup_light);
-    model.backup_light_remarks = Set(inspection.backup_light_remarks);
-    model.backup_alarm = Set(inspection.backup_alarm);
-    model.backup_alarm_remarks = Set(inspection.backup_alarm_remarks);
-    model.wiper_blades = Set(inspection.wiper_blades);
-    model.wiper_blades_remarks = Set(inspection.wiper_blades_remarks);
-    model.horn = Set(inspection.horn);
-    model.horn_remarks = Set(inspection.horn_remarks);
-    model.seats = Set(inspection.seats);
-    model.seats_remarks = Set(inspection.seats_remarks);
-    model.restraints = Set(inspection.restraints);
-    model.restraints_remarks = Set(inspection.restraints_remarks);
-    model.mirrors = Set(inspection.mirrors);
-    model.mirrors_remarks = Set(inspection.mirrors_remarks);
-    model.beacon_light = Set(inspection.beacon_light);
-    model.beacon_light_remarks = Set(inspection.beacon_light_remarks);
-    model.wiring = Set(inspection.wiring);
-    model.wiring_remarks = Set(inspection.wiring_remarks);
-    model.brakes = Set(inspection.brakes);
-    model.brakes_remarks = Set(inspection.brakes_remarks);
-    model.battery = Set(inspection.battery);
-    model.battery_remarks = Set(inspection.battery_remarks);
-    model.brake_fluid = Set(inspection.brake_fluid);
-    model.brake_fluid_remarks = Set(inspection.brake_fluid_remarks);
-    model.exhaust_system = Set(inspection.exhaust_system);
-    model.exhaust_system_remarks = Set(inspection.exhaust_system_remarks);
-    model.oil_level = Set(inspection.oil_level);
-    model.oil_level_remarks = Set(inspection.oil_level_remarks);
-    model.oil_last_change = Set(inspection.oil_last_change);
-    model.coolant = Set(inspection.coolant);
-    model.coolant_remarks = Set(inspection.coolant_remarks);
-    model.belts_hoses = Set(inspection.belts_hoses);
-    model.belts_hoses_remarks = Set(inspection.belts_hoses_remarks);
-    model.transmission = Set(inspection.transmission);
-    model.transmission_remarks = Set(inspection.transmission_remarks);
-    model.battery_cables = Set(inspection.battery_cables);
-    model.battery_cables_remarks = Set(inspection.battery_cables_remarks);
-    model.air_filter = Set(inspection.air_filter);
-    model.air_filter_remarks = Set(inspection.air_filter_remarks);
-    model.body = Set(inspection.body);
-    model.body_remarks = Set(inspection.body_remarks);
-    model.paint = Set(inspection.paint);
-    model.paint_remarks = Set(inspection.paint_remarks);
-    model.bumpers = Set(inspection.bumpers);
-    model.bumpers_remarks = Set(inspection.bumpers_remarks);
-    model.tire_tread = Set(inspection.tire_tread);
-    model.tire_tread_remarks = Set(inspection.tire_tread_remarks);
-    model.tire_gauge_present = Set(inspection.tire_gauge_present);
-    model.front_recommended_pressure = Set(inspection.front_recommended_pressure);
-    model.rear_recommended_pressure = Set(inspection.rear_recommended_pressure);
-    model.left_front_pressure = Set(inspection.left_front_pressure);
-    model.right_front_pressure = Set(inspection.right_front_pressure);
-    model.left_rear_pressure = Set(inspection.left_rear_pressure);
-    model.right_rear_pressure = Set(inspection.right_rear_pressure);
-    model.logbook_present = Set(inspection.logbook_present);
-    model.registration_present = Set(inspection.registration_present);
-    model.registration_present_remarks = Set(inspection.registration_present_remarks);
-    model.registration_current = Set(inspection.registration_current);
-    model.registration_current_remarks = Set(inspection.registration_current_remarks);
-    model.insurance_present = Set(inspection.insurance_present);
-    model.insurance_present_remarks = Set(inspection.insurance_present_remarks);
-    model.insurance_current = Set(inspection.insurance_current);
-    model.insurance_current_remarks = Set(inspection.insurance_current_remarks);
-    model.capf132_present = Set(inspection.capf132_present);
-    model.capf132_present_remarks = Set(inspection.capf132_present_remarks);
-    model.capf132_current = Set(inspection.capf132_current);
-    model.capf132_current_remarks = Set(inspection.capf132_current_remarks);
-    model.capf132_signed = Set(inspection.capf132_signed);
-    model.capf132_signed_remarks = Set(inspection.capf132_signed_remarks);
-    model.first_aid_kit_present = Set(inspection.first_aid_kit_present);
-    model.first_aid_kit_present_remarks = Set(inspection.first_aid_kit_present_remarks);
-    model.toolkit_present = Set(inspection.toolkit_present);
-    model.toolkit_present_remarks = Set(inspection.toolkit_present_remarks);
-    model.toolkit_secured = Set(inspection.toolkit_secured);
-    model.toolkit_secured_remarks = Set(inspection.toolkit_secured_remarks);
-    model.survival_kit_present = Set(inspection.survival_kit_present);
-    model.survival_kit_present_remarks = Set(inspection.survival_kit_present_remarks);
-    model.survival_kit_current = Set(inspection.survival_kit_current);
-    model.is_mission_ready = Set(inspection.is_mission_ready);
-    model.inspector_capid = Set(inspection.inspector_capid);
-
-    match model.update(&state.db).await {
-        Ok(_) => StatusCode::OK,
-        Err(DbErr::RecordNotUpdated) => StatusCode::BAD_REQUEST,
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
-    }
-}
+    let txn = match db.begin().await {
+        Ok(txn) => txn,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
 
-#[derive(Deserialize)]
-pub(crate) struct SignInspectionParameters {
-    capid: i32,
-}
+    if let Err(status) = close_trip(&txn, vehicle_id, arrival).await {
+        return status;
+    }
 
-pub(crate) async fn sign_inspection(
-    State(state): State<Arc<AppState>>,
-    Path(id): Path<i32>,
-    Json(data): Json<SignInspectionParameters>,
-) -> impl IntoResponse {
-    let mut model = match entity::vehicle_inspection::Entity::load()
-        .filter_by_id(id)
-        .one(&state.db)
-        .await
-    {
-        Ok(model) => match model {
-            Some(v) => v.into_active_model(),
-            None => return StatusCode::BAD_REQUEST,
-        },
+    let holds = match service_holds(&txn, vehicle_id).await {
+        Ok(holds) => holds,
         Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
     };
 
-    model.inspector_capid = Set(Some(data.capid));
-    model.signed_at = Set(Some(Local::now().naive_local()));
+    let active_model = entity::vehicle::ActiveModel {
+        id: Unchanged(model.id),
+        type_id: Unchanged(model.type_id),
+        year: Unchanged(model.year),
+        owned_by_unit: Unchanged(model.owned_by_unit),
+        issued_to: Set(None),
+        in_service: Set(in_service && holds.is_empty()),
+        property_tag_number: Unchanged(model.property_tag_number),
+    };
 
-    match model.update(&state.db).await {
-        Ok(_) => StatusCode::OK,
-        Err(DbErr::RecordNotUpdated) => StatusCode::BAD_REQUEST,
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    if active_model.update(&txn).await.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR;
     }
-}
 
-pub(crate) async fn override_inspection(
-    State(state): State<Arc<AppState>>,
-    Path(id): Path<i32>,
-    Json(data): Json<SignInspectionParameters>,
-) -> impl IntoResponse {
-    let mut model = match entity::vehicle_inspection::Entity::load()
-        .filter_by_id(id)
-        .one(&state.db)
+    if receipts::close(&txn, Item::Vehicle(vehicle_id))
         .await
+        .is_err()
     {
-        Ok(model) => match model {
-            Some(v) => v.into_active_model(),
-            None => return StatusCode::BAD_REQUEST,
-        },
-        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
-    };
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
 
-    model.ic_capid = Set(Some(data.capid));
-    model.ic_signed_at = Set(Some(Local::now().naive_local()));
+    if !in_service {
+        let problem = problem.unwrap_or_else(|| "Returned out of service".to_string());
 
-    match model.update(&state.db).await {
-        Ok(_) => StatusCode::OK,
-        Err(DbErr::RecordNotUpdated) => StatusCode::BAD_REQUEST,
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        if ground_vehicle(&txn, vehicle_id, problem).await.is_err() {
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
     }
-}
 
-pub(crate) async fn delete_inspection(
-    State(state): State<Arc<AppState>>,
-    Path(id): Path<i32>,
-) -> impl IntoResponse {
-    match entity::vehicle_inspection::Entity::delete_by_id(id)
-        .exec(&state.db)
-        .await
-    {
+    match txn.commit().await {
         Ok(_) => StatusCode::OK,
-        Err(DbErr::RecordNotFound(_)) => StatusCode::NOT_FOUND,
         Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
     }
 }
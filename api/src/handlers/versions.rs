@@ -0,0 +1,348 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::Json;
+use chrono::{Local, NaiveDateTime};
+use entity::prelude::{
+    Activity, Attendee, Flight, Location, Notification, Schedule, ScheduleDay, TimeBlock,
+};
+use sea_orm::{
+    ActiveValue::*, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::AppState;
+
+pub(crate) const ADDED: &str = "added";
+pub(crate) const CANCELLED: &str = "cancelled";
+pub(crate) const MOVED: &str = "moved";
+const CHANGED: &str = "changed";
+
+/// Where and when an activity happens, and who's in it, in one version.
+#[derive(Clone, PartialEq, Serialize)]
+pub(crate) struct Placement {
+    block: String,
+    starts_at: NaiveDateTime,
+    ends_at: NaiveDateTime,
+    location: Option<String>,
+    flights: Vec<String>,
+    staff: Vec<i32>,
+}
+
+impl Placement {
+    fn moved(&self, other: &Placement) -> bool {
+        self.starts_at != other.starts_at
+            || self.ends_at != other.ends_at
+            || self.location != other.location
+    }
+
+    fn when(&self) -> String {
+        let time = format!(
+            "{} {}-{}",
+            self.starts_at.format("%d %b"),
+            self.starts_at.format("%H%M"),
+            self.ends_at.format("%H%M")
+        );
+        match &self.location {
+            Some(location) => format!("{time} in {location}"),
+            None => time,
+        }
+    }
+}
+
+struct Entry {
+    name: String,
+    description: Option<String>,
+    placement: Placement,
+    flights: BTreeSet<i32>,
+    staff: BTreeSet<i32>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct Change {
+    kind: &'static str,
+    uid: Uuid,
+    name: String,
+    before: Option<Placement>,
+    after: Option<Placement>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ScheduleDiff {
+    from: entity::schedule::Model,
+    to: entity::schedule::Model,
+    changes: Vec<Change>,
+}
+
+/// A version's activities keyed by `uid`, which is what ties an activity to
+/// itself across versions.
+async fn entries<C: ConnectionTrait>(
+    db: &C,
+    schedule_id: i32,
+) -> Result<HashMap<Uuid, Entry>, DbErr> {
+    let days = ScheduleDay::find()
+        .filter(entity::schedule_day::Column::ScheduleId.eq(schedule_id))
+        .all(db)
+        .await?;
+
+    let blocks = TimeBlock::find()
+        .filter(entity::time_block::Column::DayId.is_in(days.iter().map(|d| d.id)))
+        .all(db)
+        .await?;
+
+    let activities = Activity::load()
+        .filter(entity::activity::Column::BlockId.is_in(blocks.iter().map(|b| b.id)))
+        .with(TimeBlock)
+        .with(Location)
+        .with(Flight)
+        .with(Attendee)
+        .all(db)
+        .await?;
+
+    let mut entries = HashMap::new();
+    for activity in activities {
+        let Some(block) = activity.block.as_ref() else {
+            continue;
+        };
+
+        let flights: BTreeMap<i32, String> = activity
+            .flights
+            .iter()
+            .map(|f| (f.id, f.name.clone()))
+            .collect();
+        let staff: BTreeSet<i32> = activity.staff.iter().map(|a| a.capid).collect();
+
+        entries.insert(
+            activity.uid,
+            Entry {
+                placement: Placement {
+                    block: block.name.clone(),
+                    starts_at: block.starts_at,
+                    ends_at: block.ends_at,
+                    location: activity.location.as_ref().map(|l| l.name.clone()),
+                    flights: flights.values().cloned().collect(),
+                    staff: staff.iter().copied().collect(),
+                },
+                name: activity.name.clone(),
+                description: activity.description.clone(),
+                flights: flights.into_keys().collect(),
+                staff,
+            },
+        );
+    }
+
+    Ok(entries)
+}
+
+fn diff(before: &HashMap<Uuid, Entry>, after: &HashMap<Uuid, Entry>) -> Vec<Change> {
+    let uids: BTreeSet<&Uuid> = before.keys().chain(after.keys()).collect();
+
+    let mut changes = vec![];
+    for uid in uids {
+        let (old, new) = (before.get(uid), after.get(uid));
+
+        let kind = match (old, new) {
+            (None, Some(_)) => ADDED,
+            (Some(_), None) => CANCELLED,
+            (Some(old), Some(new)) if old.placement.moved(&new.placement) => MOVED,
+            (Some(old), Some(new))
+                if old.name != new.name
+                    || old.description != new.description
+                    || old.placement != new.placement =>
+            {
+                CHANGED
+            }
+            _ => continue,
+        };
+
+        changes.push(Change {
+            kind,
+            uid: *uid,
+            name: new.or(old).map(|e| e.name.clone()).unwrap_or_default(),
+            before: old.map(|e| e.placement.clone()),
+            after: new.map(|e| e.placement.clone()),
+        });
+    }
+
+    changes.sort_by_key(|c| c.after.as_ref().or(c.before.as_ref()).map(|p| p.starts_at));
+    changes
+}
+
+/// The published version of a series before the given one, if there is one.
+async fn previous_published<C: ConnectionTrait>(
+    db: &C,
+    schedule: &entity::schedule::Model,
+) -> Result<Option<entity::schedule::Model>, DbErr> {
+    Schedule::find()
+        .filter(entity::schedule::Column::SeriesId.eq(schedule.series_id))
+        .filter(entity::schedule::Column::Version.lt(schedule.version))
+        .filter(entity::schedule::Column::PublishedAt.is_not_null())
+        .order_by_desc(entity::schedule::Column::Version)
+        .one(db)
+        .await
+}
+
+/// Every published version of the schedule's series, oldest first.
+pub(crate) async fn get_schedule_versions(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<entity::schedule::Model>>, StatusCode> {
+    let schedule = match Schedule::find_by_id(id).one(&state.db).await {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    match Schedule::find()
+        .filter(entity::schedule::Column::SeriesId.eq(schedule.series_id))
+        .filter(entity::schedule::Column::PublishedAt.is_not_null())
+        .order_by_asc(entity::schedule::Column::Version)
+        .all(&state.db)
+        .await
+    {
+        Ok(versions) => Ok(Json(versions)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// What changed in a schedule since another version of it, `?from=` by
+/// version number. Without one it's compared to the last published version.
+pub(crate) async fn get_schedule_diff(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<ScheduleDiff>, StatusCode> {
+    let to = match Schedule::find_by_id(id).one(&state.db).await {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let from = match params.get("from") {
+        Some(s) => {
+            let version: i32 = s.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+            Schedule::find()
+                .filter(entity::schedule::Column::SeriesId.eq(to.series_id))
+                .filter(entity::schedule::Column::Version.eq(version))
+                .one(&state.db)
+                .await
+        }
+        None => previous_published(&state.db, &to).await,
+    };
+
+    let from = match from {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let before = entries(&state.db, from.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let after = entries(&state.db, to.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(ScheduleDiff {
+        changes: diff(&before, &after),
+        from,
+        to,
+    }))
+}
+
+/// Tells the flights and staff in each activity what happened to it since the
+/// last published version. Someone newly put on an activity hears it was
+/// added and someone taken off hears it was cancelled, even if it carried on
+/// without them. The first published version doesn't notify anyone.
+pub(crate) async fn notify_changes<C: ConnectionTrait>(
+    db: &C,
+    schedule: &entity::schedule::Model,
+) -> Result<(), DbErr> {
+    let Some(previous) = previous_published(db, schedule).await? else {
+        return Ok(());
+    };
+
+    let before = entries(db, previous.id).await?;
+    let after = entries(db, schedule.id).await?;
+    let now = Local::now().naive_local();
+
+    let mut notifications = vec![];
+    for uid in before.keys().chain(after.keys()).collect::<BTreeSet<_>>() {
+        let (old, new) = (before.get(uid), after.get(uid));
+        let Some(name) = new.or(old).map(|e| e.name.as_str()) else {
+            continue;
+        };
+
+        let mut notify = |capid: Option<i32>, flight_id: Option<i32>, was: bool, is: bool| {
+            let (kind, message) = match (old, new) {
+                (Some(old), Some(new)) if was && is => {
+                    if !old.placement.moved(&new.placement) {
+                        return;
+                    }
+                    (
+                        MOVED,
+                        format!(
+                            "{name} moved from {} to {}",
+                            old.placement.when(),
+                            new.placement.when()
+                        ),
+                    )
+                }
+                (_, Some(new)) if is => (ADDED, format!("{name} added {}", new.placement.when())),
+                (Some(old), _) if was => (
+                    CANCELLED,
+                    format!("{name} {} cancelled", old.placement.when()),
+                ),
+                _ => return,
+            };
+
+            notifications.push(entity::notification::ActiveModel {
+                id: NotSet,
+                capid: Set(capid),
+                flight_id: Set(flight_id),
+                schedule_id: Set(schedule.id),
+                activity_uid: Set(*uid),
+                kind: Set(kind.to_string()),
+                message: Set(message),
+                created_at: Set(now),
+            });
+        };
+
+        let flights: BTreeSet<i32> = old
+            .into_iter()
+            .chain(new)
+            .flat_map(|e| e.flights.iter().copied())
+            .collect();
+        for flight in flights {
+            notify(
+                None,
+                Some(flight),
+                old.is_some_and(|e| e.flights.contains(&flight)),
+                new.is_some_and(|e| e.flights.contains(&flight)),
+            );
+        }
+
+        let staff: BTreeSet<i32> = old
+            .into_iter()
+            .chain(new)
+            .flat_map(|e| e.staff.iter().copied())
+            .collect();
+        for capid in staff {
+            notify(
+                Some(capid),
+                None,
+                old.is_some_and(|e| e.staff.contains(&capid)),
+                new.is_some_and(|e| e.staff.contains(&capid)),
+            );
+        }
+    }
+
+    if !notifications.is_empty() {
+        Notification::insert_many(notifications).exec(db).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use chrono::{Local, NaiveDateTime, TimeZone, Utc};
+
+/// Longest content line allowed before folding, in octets.
+const LINE_LIMIT: usize = 75;
+
+pub(crate) struct Event<'a> {
+    pub(crate) uid: String,
+    pub(crate) sequence: i32,
+    pub(crate) starts_at: NaiveDateTime,
+    pub(crate) ends_at: NaiveDateTime,
+    pub(crate) summary: &'a str,
+    pub(crate) location: Option<&'a str>,
+    pub(crate) description: Option<String>,
+}
+
+/// An RFC 5545 calendar for subscribing to from a phone or desktop client.
+///
+/// Event times are stored in the encampment's local time, so they're
+/// converted to UTC here for clients in other zones.
+pub(crate) struct Calendar {
+    lines: Vec<String>,
+}
+
+impl Calendar {
+    pub(crate) fn new(name: &str) -> Self {
+        Calendar {
+            lines: vec![
+                "BEGIN:VCALENDAR".to_string(),
+                "VERSION:2.0".to_string(),
+                format!("PRODID:-//CampFIRE//{}//EN", env!("CARGO_PKG_VERSION")),
+                "CALSCALE:GREGORIAN".to_string(),
+                "METHOD:PUBLISH".to_string(),
+                format!("X-WR-CALNAME:{}", escape(name)),
+            ],
+        }
+    }
+
+    pub(crate) fn event(&mut self, event: Event) {
+        self.lines.push("BEGIN:VEVENT".to_string());
+        self.lines.push(format!("UID:{}", event.uid));
+        self.lines.push(format!("SEQUENCE:{}", event.sequence));
+        self.lines
+            .push(format!("DTSTAMP:{}", Utc::now().format("%Y%m%dT%H%M%SZ")));
+        self.lines.push(format!("DTSTART:{}", utc(event.starts_at)));
+        self.lines.push(format!("DTEND:{}", utc(event.ends_at)));
+        self.lines
+            .push(format!("SUMMARY:{}", escape(event.summary)));
+        if let Some(location) = event.location {
+            self.lines.push(format!("LOCATION:{}", escape(location)));
+        }
+        if let Some(description) = event.description {
+            self.lines
+                .push(format!("DESCRIPTION:{}", escape(&description)));
+        }
+        self.lines.push("END:VEVENT".to_string());
+    }
+
+    pub(crate) fn finish(mut self) -> String {
+        self.lines.push("END:VCALENDAR".to_string());
+
+        let mut out = String::new();
+        for line in self.lines {
+            fold(&line, &mut out);
+        }
+        out
+    }
+}
+
+fn utc(time: NaiveDateTime) -> String {
+    match Local.from_local_datetime(&time).earliest() {
+        Some(local) => local
+            .with_timezone(&Utc)
+            .format("%Y%m%dT%H%M%SZ")
+            .to_string(),
+        // Skipped by a DST change; leave it floating rather than guess
+        None => time.format("%Y%m%dT%H%M%S").to_string(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn fold(line: &str, out: &mut String) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > LINE_LIMIT {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+pub(crate) fn response(file_name: &str, body: String) -> Response {
+    (
+        [
+            (
+                header::CONTENT_TYPE,
+                "text/calendar; charset=utf-8".to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{file_name}\""),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use axum::extract::Multipart;
+use axum::http::StatusCode;
+use calamine::{HeaderRow, Reader, Xlsx, XlsxError, open_workbook};
+use serde::Serialize;
+
+const XLSX: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+pub(crate) enum UploadKind {
+    Xlsx,
+    Csv,
+}
+
+/// A spreadsheet upload, saved to the uploads folder so the original file is
+/// kept alongside the records it produced.
+pub(crate) struct Upload {
+    pub(crate) path: PathBuf,
+    pub(crate) kind: UploadKind,
+}
+
+pub(crate) async fn save_upload(multipart: &mut Multipart) -> Result<Upload, StatusCode> {
+    let mut upload = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|_| StatusCode::BAD_REQUEST)?
+    {
+        let file_name = field.file_name().unwrap_or_default().to_string();
+        let content_type = field.content_type().unwrap_or_default().to_string();
+
+        let kind = match content_type.as_str() {
+            XLSX => UploadKind::Xlsx,
+            "text/csv" | "application/csv" => UploadKind::Csv,
+            _ if file_name.to_lowercase().ends_with(".csv") => UploadKind::Csv,
+            _ => return Err(StatusCode::BAD_REQUEST),
+        };
+
+        let data = field.bytes().await.map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        let mut path = PathBuf::from(dotenvy::var("UPLOADS_PATH").unwrap());
+        path.push(format!(
+            "{}_{}",
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_millis(),
+            sanitize_filename::sanitize(file_name)
+        ));
+
+        tokio::fs::write(&path, data)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        upload = Some(Upload { path, kind });
+    }
+
+    upload.ok_or(StatusCode::BAD_REQUEST)
+}
+
+/// A spreadsheet row keyed by its lowercased column header.
+pub(crate) struct Row {
+    pub(crate) number: usize,
+    cells: HashMap<String, String>,
+}
+
+impl Row {
+    /// Returns the first non-empty cell under any of the given headers.
+    pub(crate) fn get(&self, headers: &[&str]) -> Option<&str> {
+        headers
+            .iter()
+            .filter_map(|h| self.cells.get(*h))
+            .map(|v| v.trim())
+            .find(|v| !v.is_empty())
+    }
+
+    pub(crate) fn require(&self, headers: &[&str]) -> Result<&str, String> {
+        self.get(headers)
+            .ok_or_else(|| format!("Missing value for \"{}\"", headers[0]))
+    }
+
+    pub(crate) fn parse<T: std::str::FromStr>(&self, headers: &[&str]) -> Result<T, String> {
+        let value = self.require(headers)?;
+
+        value
+            .parse()
+            .map_err(|_| format!("Invalid value \"{value}\" for \"{}\"", headers[0]))
+    }
+
+    pub(crate) fn flag(&self, headers: &[&str], default: bool) -> Result<bool, String> {
+        match self.get(headers).map(str::to_lowercase).as_deref() {
+            None => Ok(default),
+            Some("yes" | "y" | "true" | "1") => Ok(true),
+            Some("no" | "n" | "false" | "0") => Ok(false),
+            Some(value) => Err(format!("Invalid value \"{value}\" for \"{}\"", headers[0])),
+        }
+    }
+}
+
+/// Reads the first worksheet of an upload, treating the first non-empty row
+/// as the header. Row numbers match what the user sees in their spreadsheet.
+pub(crate) fn read_rows(upload: &Upload) -> Result<Vec<Row>, String> {
+    let mut table: Vec<Vec<String>> = vec![];
+    let mut first_row = 0;
+
+    match upload.kind {
+        UploadKind::Xlsx => {
+            let mut excel: Xlsx<_> =
+                open_workbook(&upload.path).map_err(|e: XlsxError| e.to_string())?;
+            let sheet = excel
+                .with_header_row(HeaderRow::FirstNonEmptyRow)
+                .worksheet_range_at(0)
+                .ok_or("Workbook has no worksheets")?
+                .map_err(|e| e.to_string())?;
+
+            first_row = sheet.start().map(|(row, _)| row as usize).unwrap_or(0);
+            for row in sheet.rows() {
+                table.push(row.iter().map(|c| c.to_string()).collect());
+            }
+        }
+        UploadKind::Csv => {
+            let mut reader = csv::ReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .from_path(&upload.path)
+                .map_err(|e| e.to_string())?;
+
+            for record in reader.records() {
+                let record = record.map_err(|e| e.to_string())?;
+                table.push(record.iter().map(str::to_string).collect());
+            }
+        }
+    }
+
+    let mut rows = table.into_iter().enumerate();
+    let headers: Vec<String> = match rows.next() {
+        Some((_, header)) => header.iter().map(|h| h.trim().to_lowercase()).collect(),
+        None => return Ok(vec![]),
+    };
+
+    Ok(rows
+        .filter(|(_, cells)| cells.iter().any(|c| !c.trim().is_empty()))
+        .map(|(i, cells)| Row {
+            number: first_row + i + 1,
+            cells: headers.iter().cloned().zip(cells).collect(),
+        })
+        .collect())
+}
+
+#[derive(Serialize)]
+pub(crate) struct RowError {
+    row: usize,
+    message: String,
+}
+
+/// Outcome of a bulk import. Rows that fail are reported and skipped so one
+/// bad line doesn't throw away the rest of the sheet.
+#[derive(Default, Serialize)]
+pub(crate) struct ImportReport {
+    imported: usize,
+    errors: Vec<RowError>,
+}
+
+impl ImportReport {
+    pub(crate) fn record(&mut self, row: usize, result: Result<(), String>) {
+        match result {
+            Ok(()) => self.imported += 1,
+            Err(message) => self.errors.push(RowError { row, message }),
+        }
+    }
+}
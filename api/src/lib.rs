@@ -3,21 +3,48 @@ use std::sync::Arc;
 
 use axum::Router;
 use axum::http::{HeaderValue, Method};
-use axum::routing::{get, post};
+use axum::routing::{delete, get, post};
 use migration::{Migrator, MigratorTrait};
 use sea_orm::Database;
 use tokio::net::TcpListener;
 use tower_http::cors::{Any, CorsLayer};
 
+use crate::config::Config;
 use crate::handlers::AppState;
+use crate::handlers::approvals::*;
+use crate::handlers::assets::*;
 use crate::handlers::attendees::*;
+use crate::handlers::calendars::*;
+use crate::handlers::callsigns::*;
+use crate::handlers::conflicts::*;
+use crate::handlers::drivers::*;
+use crate::handlers::facilities::*;
+use crate::handlers::flights::*;
 use crate::handlers::headcount::*;
+use crate::handlers::incidents::*;
+use crate::handlers::inspections::*;
+use crate::handlers::locations::*;
+use crate::handlers::lodging::*;
+use crate::handlers::maintenance::*;
+use crate::handlers::notices::*;
+use crate::handlers::notifications::*;
+use crate::handlers::plans::*;
 use crate::handlers::radios::*;
+use crate::handlers::receipts::*;
+use crate::handlers::reservations::*;
 use crate::handlers::root::root;
+use crate::handlers::schedules::*;
+use crate::handlers::trips::*;
 use crate::handlers::uploads::*;
 use crate::handlers::vehicles::*;
+use crate::handlers::versions::*;
 
+mod config;
+mod export;
 mod handlers;
+mod ical;
+mod import;
+mod pdf;
 
 #[tokio::main]
 async fn start() -> anyhow::Result<()> {
@@ -33,13 +60,12 @@ async fn start() -> anyhow::Result<()> {
     let bind_addr = dotenvy::var("BIND_ADDR").expect("BIND_ADDR is not set in .env file");
     let uploads_path = dotenvy::var("UPLOADS_PATH").expect("UPLOADS_PATH is not set in .env file");
 
-    let debug = match dotenvy::var("DEBUG")
-        .expect("DEBUG is not set in .env file")
-        .as_str()
-    {
-        "true" => true,
-        _ => false,
-    };
+    let debug = matches!(
+        dotenvy::var("DEBUG")
+            .expect("DEBUG is not set in .env file")
+            .as_str(),
+        "true"
+    );
 
     match tokio::fs::try_exists(uploads_path.clone()).await {
         Ok(true) => {}
@@ -54,6 +80,7 @@ async fn start() -> anyhow::Result<()> {
             .await
             .expect("Database connection failed"),
         version: env!("CARGO_PKG_VERSION").to_string(),
+        config: Config::from_env(),
     });
 
     Migrator::up(&state.db, None).await?;
@@ -81,8 +108,44 @@ async fn start() -> anyhow::Result<()> {
             "/attendees/{id}",
             get(get_attendee_by_capid).delete(delete_attendee),
         )
+        .route(
+            "/attendees/{id}/depart",
+            post(mark_departed).delete(clear_departed),
+        )
+        .route("/attendees/roles", get(get_all_staff_roles))
+        .route("/attendees/{id}/roles", post(add_staff_role))
+        .route("/attendees/{id}/roles/{role}", delete(remove_staff_role))
+        .route("/attendees/{id}/driver", get(get_driver_eligibility))
+        .route("/attendees/{id}/now", get(get_attendee_now_next))
+        .route("/attendees/{id}/license", post(set_cap_license))
         .route("/attendees/new", post(create_attendee))
         .route("/attendees/new/bulk", post(create_attendee_bulk))
+        .route(
+            "/facilities/requests",
+            get(get_all_facility_requests).post(create_facility_request),
+        )
+        .route(
+            "/facilities/requests/{id}",
+            get(get_facility_request)
+                .post(update_facility_request)
+                .delete(delete_facility_request),
+        )
+        .route(
+            "/facilities/requests/{id}/status",
+            post(set_facility_request_status),
+        )
+        .route(
+            "/facilities/requests/{id}/assign",
+            post(assign_facility_request),
+        )
+        .route(
+            "/facilities/requests/{id}/comments",
+            post(add_facility_request_comment),
+        )
+        .route(
+            "/facilities/requests/{id}/photos",
+            post(upload_facility_request_photos),
+        )
         .route("/headcounts", get(get_all_headcounts))
         .route(
             "/headcounts/{id}",
@@ -92,7 +155,96 @@ async fn start() -> anyhow::Result<()> {
             "/headcounts/{id}/manage",
             post(add_to_headcount).delete(remove_from_headcount),
         )
+        .route(
+            "/headcounts/{id}/accountability",
+            get(get_headcount_accountability),
+        )
         .route("/headcounts/new", post(create_headcount))
+        .route("/schedules", get(get_all_schedules).post(create_schedule))
+        .route(
+            "/schedules/{id}",
+            get(get_schedule)
+                .post(update_schedule)
+                .delete(delete_schedule),
+        )
+        .route("/schedules/stages", get(get_approval_stages))
+        .route("/schedules/plan", get(get_plan_of_the_day))
+        .route("/schedules/{id}/conflicts", get(get_schedule_conflicts))
+        .route("/schedules/{id}/submit", post(submit_schedule))
+        .route("/schedules/{id}/review", post(review_schedule))
+        .route("/schedules/{id}/reviews", get(get_schedule_reviews))
+        .route("/schedules/{id}/revise", post(revise_schedule))
+        .route("/schedules/{id}/versions", get(get_schedule_versions))
+        .route("/schedules/{id}/diff", get(get_schedule_diff))
+        .route("/schedules/{id}/days", post(add_schedule_day))
+        .route(
+            "/schedules/days/{id}",
+            post(update_schedule_day).delete(delete_schedule_day),
+        )
+        .route("/schedules/days/{id}/blocks", post(add_time_block))
+        .route(
+            "/schedules/blocks/{id}",
+            post(update_time_block).delete(delete_time_block),
+        )
+        .route("/schedules/blocks/{id}/activities", post(add_activity))
+        .route(
+            "/schedules/activities/{id}",
+            get(get_activity)
+                .post(update_activity)
+                .delete(delete_activity),
+        )
+        .route(
+            "/calendars",
+            get(get_all_calendar_feeds).post(create_calendar_feed),
+        )
+        .route("/calendars/{id}", delete(delete_calendar_feed))
+        .route("/calendars/feed/{token}", get(get_calendar_feed))
+        .route(
+            "/lodging/buildings",
+            get(get_all_buildings).post(create_building),
+        )
+        .route(
+            "/lodging/buildings/{id}",
+            post(update_building).delete(delete_building),
+        )
+        .route("/lodging/buildings/{id}/rooms", post(create_room))
+        .route(
+            "/lodging/rooms/{id}",
+            get(get_room).post(update_room).delete(delete_room),
+        )
+        .route(
+            "/lodging/rooms/{id}/beds",
+            post(assign_bed).delete(unassign_bed),
+        )
+        .route("/lodging/roster/pdf", get(get_room_roster_pdf))
+        .route("/lodging/roster/export", get(export_room_roster))
+        .route("/notices", get(get_all_notices).post(create_notice))
+        .route("/notices/{id}", delete(delete_notice))
+        .route("/notifications", get(get_all_notifications))
+        .route("/notifications/{id}/read", post(mark_notification_read))
+        .route("/locations", get(get_all_locations).post(create_location))
+        .route(
+            "/locations/{id}",
+            post(update_location).delete(delete_location),
+        )
+        .route("/flights", get(get_all_flights).post(create_flight))
+        .route(
+            "/flights/{id}",
+            get(get_flight).post(update_flight).delete(delete_flight),
+        )
+        .route(
+            "/flights/{id}/members",
+            post(add_flight_member).delete(remove_flight_member),
+        )
+        .route("/flights/{id}/now", get(get_flight_now_next))
+        .route("/assets", get(get_asset_by_tag))
+        .route("/assets/issue", post(issue_asset).delete(return_asset))
+        .route("/assets/outstanding", get(get_turn_in_report))
+        .route("/assets/outstanding/csv", get(export_turn_in_report))
+        .route("/receipts", get(get_all_receipts))
+        .route("/receipts/{id}", get(get_receipt))
+        .route("/receipts/{id}/pdf", get(get_receipt_pdf))
+        .route("/receipts/{id}/sign", post(sign_receipt))
         .route("/uploads", get(get_all_uploads))
         .route("/uploads/file", get(download_file))
         .route(
@@ -107,13 +259,37 @@ async fn start() -> anyhow::Result<()> {
                 .post(create_new_radio_type)
                 .delete(delete_radio_type),
         )
+        .route("/radios/bulk", post(create_radio_bulk))
         .route("/radios/issue", post(issue_radio).delete(return_radio))
+        .route(
+            "/radios/tickets",
+            get(get_all_radio_tickets).post(create_radio_ticket),
+        )
+        .route(
+            "/radios/tickets/{id}",
+            get(get_radio_ticket)
+                .post(update_radio_ticket)
+                .delete(delete_radio_ticket),
+        )
+        .route("/radios/tickets/{id}/sign", post(return_radio_to_service))
+        .route(
+            "/callsigns",
+            get(get_all_callsigns)
+                .post(create_new_callsign)
+                .delete(delete_callsign),
+        )
+        .route(
+            "/callsigns/assign",
+            post(assign_callsign).delete(unassign_callsign),
+        )
+        .route("/callsigns/roster", get(get_net_roster))
         .route(
             "/vehicles",
             get(get_all_vehicles)
                 .post(create_new_vehicle)
                 .delete(delete_vehicle),
         )
+        .route("/vehicles/bulk", post(create_vehicle_bulk))
         .route(
             "/vehicles/types",
             get(get_all_vehicle_types)
@@ -122,18 +298,73 @@ async fn start() -> anyhow::Result<()> {
         )
         .route(
             "/vehicles/issue",
-            post(issue_vehicle).delete(return_vehicle),
+            get(get_issue_prefill)
+                .post(issue_vehicle)
+                .delete(return_vehicle),
+        )
+        .route(
+            "/vehicles/incidents",
+            get(get_all_incidents).post(create_incident),
+        )
+        .route(
+            "/vehicles/incidents/{id}",
+            get(get_incident).delete(delete_incident),
+        )
+        .route("/vehicles/incidents/{id}/pdf", get(get_incident_pdf))
+        .route("/vehicles/incidents/{id}/clear", post(clear_incident))
+        .route(
+            "/vehicles/incidents/{id}/photos",
+            post(upload_incident_photos),
+        )
+        .route(
+            "/vehicles/incidents/{id}/witnesses",
+            post(add_incident_witness),
+        )
+        .route(
+            "/vehicles/reservations",
+            get(get_all_reservations).post(create_reservation),
+        )
+        .route(
+            "/vehicles/reservations/{id}",
+            get(get_reservation)
+                .post(update_reservation)
+                .delete(delete_reservation),
         )
+        .route(
+            "/vehicles/reservations/calendar",
+            get(get_reservation_calendar),
+        )
+        .route("/vehicles/overrides", get(get_all_driver_overrides))
+        .route("/vehicles/deficiencies", get(get_all_vehicle_deficiencies))
+        .route(
+            "/vehicles/deficiencies/{id}",
+            get(get_vehicle_deficiency)
+                .post(update_vehicle_deficiency)
+                .delete(delete_vehicle_deficiency),
+        )
+        .route(
+            "/vehicles/deficiencies/{id}/sign",
+            post(close_vehicle_deficiency),
+        )
+        .route("/vehicles/trips", get(get_all_trips))
+        .route("/vehicles/trips/{id}", get(get_trip))
+        .route("/vehicles/trips/mileage", get(get_mileage))
+        .route("/vehicles/trips/csv", get(export_trips))
         .route(
             "/vehicles/inspect",
             get(get_all_inspections).post(start_inspection),
         )
+        .route(
+            "/vehicles/inspect/templates",
+            get(get_all_inspection_templates).post(create_inspection_template),
+        )
         .route(
             "/vehicles/inspect/{id}",
             get(get_inspection)
                 .post(update_inspection)
                 .delete(delete_inspection),
         )
+        .route("/vehicles/inspect/{id}/pdf", get(get_inspection_pdf))
         .route("/vehicles/inspect/{id}/sign", post(sign_inspection))
         .route("/vehicles/inspect/{id}/ic", post(override_inspection))
         .layer(cors)
@@ -0,0 +1,422 @@
+use std::io::Cursor;
+
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use chrono::Local;
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str};
+use png::{ColorType, Decoder, Transformations};
+
+const PAGE_WIDTH: f32 = 612.0;
+const PAGE_HEIGHT: f32 = 792.0;
+const MARGIN: f32 = 54.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+const FOOTER_HEIGHT: f32 = 36.0;
+
+const REGULAR: Name = Name(b"F1");
+const BOLD: Name = Name(b"F2");
+
+const BODY_SIZE: f32 = 10.0;
+const LINE_HEIGHT: f32 = 13.0;
+const CELL_PADDING: f32 = 3.0;
+const LABEL_WIDTH: f32 = 150.0;
+
+/// Helvetica advance widths for the printable ASCII range, in 1/1000 em.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// A printable report laid out top to bottom on US Letter pages.
+///
+/// Only the standard Helvetica faces are used, so no font data has to be
+/// embedded and every PDF viewer can render the result.
+pub(crate) struct Document {
+    title: String,
+    pages: Vec<Content>,
+    images: Vec<Image>,
+    y: f32,
+}
+
+/// A decoded raster image flattened onto a white background.
+struct Image {
+    page: usize,
+    width: u32,
+    height: u32,
+    samples: Vec<u8>,
+}
+
+impl Document {
+    pub(crate) fn new(title: &str) -> Self {
+        let mut document = Self {
+            title: title.to_string(),
+            pages: vec![],
+            images: vec![],
+            y: 0.0,
+        };
+
+        document.new_page();
+        document.write(BOLD, 16.0, MARGIN, document.y - 16.0, title);
+        document.y -= 28.0;
+
+        document
+    }
+
+    /// Writes a bold section heading, starting a new page if there is no room
+    /// left for at least one line underneath it.
+    pub(crate) fn heading(&mut self, text: &str) {
+        self.ensure_space(LINE_HEIGHT * 3.0);
+        self.y -= 6.0;
+        self.write(BOLD, 12.0, MARGIN, self.y - 12.0, text);
+        self.y -= 18.0;
+    }
+
+    /// Writes a block of text wrapped to the page width.
+    pub(crate) fn paragraph(&mut self, text: &str) {
+        for line in wrap(text, BODY_SIZE, CONTENT_WIDTH) {
+            self.ensure_space(LINE_HEIGHT);
+            self.write(REGULAR, BODY_SIZE, MARGIN, self.y - BODY_SIZE, &line);
+            self.y -= LINE_HEIGHT;
+        }
+        self.y -= 4.0;
+    }
+
+    /// Writes a bold label with its value wrapped alongside it.
+    pub(crate) fn field(&mut self, label: &str, value: &str) {
+        let lines = wrap(value, BODY_SIZE, CONTENT_WIDTH - LABEL_WIDTH);
+        self.ensure_space(LINE_HEIGHT * lines.len() as f32);
+
+        self.write(BOLD, BODY_SIZE, MARGIN, self.y - BODY_SIZE, label);
+        for line in lines {
+            self.write(
+                REGULAR,
+                BODY_SIZE,
+                MARGIN + LABEL_WIDTH,
+                self.y - BODY_SIZE,
+                &line,
+            );
+            self.y -= LINE_HEIGHT;
+        }
+    }
+
+    /// Places a PNG image, scaled down to fit within the given box.
+    pub(crate) fn image(
+        &mut self,
+        png: &[u8],
+        max_width: f32,
+        max_height: f32,
+    ) -> Result<(), String> {
+        let mut decoder = Decoder::new(Cursor::new(png));
+        decoder.set_transformations(Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+        let mut buf = vec![0; reader.output_buffer_size().ok_or("Image is too large")?];
+        let info = reader.next_frame(&mut buf).map_err(|e| e.to_string())?;
+
+        let pixels = &buf[..info.line_size * info.height as usize];
+        let samples = match info.color_type {
+            ColorType::Rgb => pixels.to_vec(),
+            ColorType::Rgba => pixels
+                .chunks(4)
+                .flat_map(|p| [p[0], p[1], p[2]].map(|c| over_white(c, p[3])))
+                .collect(),
+            ColorType::Grayscale => pixels.iter().flat_map(|&g| [g, g, g]).collect(),
+            ColorType::GrayscaleAlpha => pixels
+                .chunks(2)
+                .flat_map(|p| [over_white(p[0], p[1]); 3])
+                .collect(),
+            ColorType::Indexed => return Err("Unsupported image format".to_string()),
+        };
+
+        let scale = (max_width / info.width as f32)
+            .min(max_height / info.height as f32)
+            .min(1.0);
+        let width = info.width as f32 * scale;
+        let height = info.height as f32 * scale;
+
+        self.ensure_space(height);
+
+        let name = format!("Im{}", self.images.len());
+        let y = self.y - height;
+        let content = self.pages.last_mut().unwrap();
+        content.save_state();
+        content.transform([width, 0.0, 0.0, height, MARGIN, y]);
+        content.x_object(Name(name.as_bytes()));
+        content.restore_state();
+
+        self.images.push(Image {
+            page: self.pages.len() - 1,
+            width: info.width,
+            height: info.height,
+            samples,
+        });
+        self.y -= height + 4.0;
+
+        Ok(())
+    }
+
+    /// Draws a line to sign on with a caption underneath.
+    pub(crate) fn signature_line(&mut self, caption: &str) {
+        self.ensure_space(LINE_HEIGHT * 3.0);
+        self.y -= LINE_HEIGHT * 1.5;
+
+        let y = self.y;
+        let content = self.pages.last_mut().unwrap();
+        content.set_line_width(0.5);
+        content.move_to(MARGIN, y);
+        content.line_to(MARGIN + CONTENT_WIDTH / 2.0, y);
+        content.stroke();
+
+        self.write(REGULAR, 8.0, MARGIN, self.y - 10.0, caption);
+        self.y -= LINE_HEIGHT * 1.5;
+    }
+
+    /// Writes a ruled table. Column widths are fractions of the page width and
+    /// cell text wraps within its column. The header row is repeated whenever
+    /// the table continues onto a new page.
+    pub(crate) fn table(&mut self, columns: &[(&str, f32)], rows: &[Vec<String>]) {
+        let widths: Vec<f32> = columns.iter().map(|(_, w)| w * CONTENT_WIDTH).collect();
+        let header: Vec<String> = columns.iter().map(|(t, _)| t.to_string()).collect();
+
+        self.ensure_space(LINE_HEIGHT * 3.0);
+        self.table_row(&widths, &header, BOLD);
+
+        for row in rows {
+            let height = row_height(&widths, row);
+            if self.y - height < MARGIN + FOOTER_HEIGHT {
+                self.new_page();
+                self.table_row(&widths, &header, BOLD);
+            }
+            self.table_row(&widths, row, REGULAR);
+        }
+
+        self.y -= 8.0;
+    }
+
+    pub(crate) fn finish(self) -> Vec<u8> {
+        let mut pdf = Pdf::new();
+
+        let catalog_id = Ref::new(1);
+        let page_tree_id = Ref::new(2);
+        let regular_id = Ref::new(3);
+        let bold_id = Ref::new(4);
+        let first_page_id = 5;
+
+        let count = self.pages.len();
+        let page_ids: Vec<Ref> = (0..count)
+            .map(|i| Ref::new(first_page_id + 2 * i as i32))
+            .collect();
+        let first_image_id = first_page_id + 2 * count as i32;
+
+        pdf.catalog(catalog_id).pages(page_tree_id);
+        pdf.pages(page_tree_id)
+            .kids(page_ids.iter().copied())
+            .count(count as i32);
+        pdf.type1_font(regular_id)
+            .base_font(Name(b"Helvetica"))
+            .encoding_predefined(Name(b"WinAnsiEncoding"));
+        pdf.type1_font(bold_id)
+            .base_font(Name(b"Helvetica-Bold"))
+            .encoding_predefined(Name(b"WinAnsiEncoding"));
+
+        let generated = Local::now().format("%d %b %Y %H:%M").to_string();
+
+        for (i, mut content) in self.pages.into_iter().enumerate() {
+            let page_id = page_ids[i];
+            let content_id = Ref::new(page_id.get() + 1);
+
+            let footer = format!("{} - generated {generated}", self.title);
+            let number = format!("Page {} of {count}", i + 1);
+            show(&mut content, REGULAR, 8.0, MARGIN, MARGIN - 12.0, &footer);
+            show(
+                &mut content,
+                REGULAR,
+                8.0,
+                PAGE_WIDTH - MARGIN - text_width(&number, 8.0),
+                MARGIN - 12.0,
+                &number,
+            );
+
+            let mut page = pdf.page(page_id);
+            page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT));
+            page.parent(page_tree_id);
+            page.contents(content_id);
+            let mut resources = page.resources();
+            resources
+                .fonts()
+                .pair(REGULAR, regular_id)
+                .pair(BOLD, bold_id);
+            let mut x_objects = resources.x_objects();
+            for (n, _) in self
+                .images
+                .iter()
+                .enumerate()
+                .filter(|(_, im)| im.page == i)
+            {
+                let name = format!("Im{n}");
+                x_objects.pair(Name(name.as_bytes()), Ref::new(first_image_id + n as i32));
+            }
+            x_objects.finish();
+            resources.finish();
+            page.finish();
+
+            pdf.stream(content_id, &content.finish());
+        }
+
+        for (n, image) in self.images.iter().enumerate() {
+            let mut xobject =
+                pdf.image_xobject(Ref::new(first_image_id + n as i32), &image.samples);
+            xobject.width(image.width as i32);
+            xobject.height(image.height as i32);
+            xobject.color_space().device_rgb();
+            xobject.bits_per_component(8);
+        }
+
+        pdf.finish()
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(Content::new());
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN + FOOTER_HEIGHT {
+            self.new_page();
+        }
+    }
+
+    fn write(&mut self, font: Name, size: f32, x: f32, y: f32, text: &str) {
+        let content = self.pages.last_mut().unwrap();
+        show(content, font, size, x, y, text);
+    }
+
+    fn table_row(&mut self, widths: &[f32], cells: &[String], font: Name) {
+        let height = row_height(widths, cells);
+        let mut x = MARGIN;
+
+        for (width, cell) in widths.iter().zip(cells) {
+            let mut y = self.y - CELL_PADDING - BODY_SIZE;
+            for line in wrap(cell, BODY_SIZE, width - 2.0 * CELL_PADDING) {
+                self.write(font, BODY_SIZE, x + CELL_PADDING, y, &line);
+                y -= LINE_HEIGHT;
+            }
+            x += width;
+        }
+
+        self.y -= height;
+
+        let y = self.y;
+        let content = self.pages.last_mut().unwrap();
+        content.set_line_width(if font == BOLD { 1.0 } else { 0.25 });
+        content.move_to(MARGIN, y);
+        content.line_to(MARGIN + CONTENT_WIDTH, y);
+        content.stroke();
+    }
+}
+
+/// Builds the response for a rendered document, displayed inline by browsers.
+pub(crate) fn response(file_name: &str, bytes: Vec<u8>) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{file_name}\""),
+            ),
+        ],
+        bytes,
+    )
+        .into_response()
+}
+
+fn show(content: &mut Content, font: Name, size: f32, x: f32, y: f32, text: &str) {
+    content.begin_text();
+    content.set_font(font, size);
+    content.next_line(x, y);
+    content.show(Str(&encode(text)));
+    content.end_text();
+}
+
+fn over_white(channel: u8, alpha: u8) -> u8 {
+    (255 - (255 - channel as u16) * alpha as u16 / 255) as u8
+}
+
+fn row_height(widths: &[f32], cells: &[String]) -> f32 {
+    let lines = widths
+        .iter()
+        .zip(cells)
+        .map(|(width, cell)| wrap(cell, BODY_SIZE, width - 2.0 * CELL_PADDING).len())
+        .max()
+        .unwrap_or(1)
+        .max(1);
+
+    lines as f32 * LINE_HEIGHT + 2.0 * CELL_PADDING
+}
+
+/// Encodes text for the WinAnsi encoded standard fonts, replacing anything
+/// outside Latin-1 with a question mark.
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c as u32 {
+            32..=126 | 160..=255 => c as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+fn text_width(text: &str, size: f32) -> f32 {
+    text.chars()
+        .map(|c| match c as u32 {
+            code @ 32..=126 => HELVETICA_WIDTHS[(code - 32) as usize],
+            _ => 556,
+        } as f32)
+        .sum::<f32>()
+        * size
+        / 1000.0
+}
+
+/// Greedily wraps text to the given width, breaking words that are too long
+/// to fit on a line of their own.
+fn wrap(text: &str, size: f32, width: f32) -> Vec<String> {
+    let mut lines = vec![];
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{line} {word}")
+            };
+
+            if text_width(&candidate, size) <= width {
+                line = candidate;
+                continue;
+            }
+
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+
+            for c in word.chars() {
+                line.push(c);
+                if text_width(&line, size) > width && line.chars().count() > 1 {
+                    line.pop();
+                    lines.push(std::mem::take(&mut line));
+                    line.push(c);
+                }
+            }
+        }
+
+        lines.push(line);
+    }
+
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+
+    lines
+}
@@ -68,6 +68,8 @@ pub struct Model {
     pub prices_id: Option<i32>,
     pub invoice_status: Option<String>,
     pub registered_by: Option<String>,
+    pub departed_at: Option<DateTime>,
+    pub cap_license_expiration: Option<Date>,
     #[sea_orm(has_many, via = "headcount_entry")]
     pub headcount_entries: HasMany<super::headcount::Entity>,
     #[sea_orm(has_many)]
@@ -75,7 +77,65 @@ pub struct Model {
     #[sea_orm(has_many)]
     pub issued_vehicles: HasMany<super::vehicle::Entity>,
     #[sea_orm(has_many)]
+    pub callsigns: HasMany<super::callsign::Entity>,
+    #[sea_orm(has_many)]
+    pub hand_receipts: HasMany<super::hand_receipt::Entity>,
+    #[sea_orm(has_many)]
+    pub radio_tickets: HasMany<super::radio_ticket::Entity>,
+    #[sea_orm(has_many)]
+    pub trips_driven: HasMany<super::vehicle_trip::Entity>,
+    #[sea_orm(has_many)]
+    pub driver_overrides: HasMany<super::driver_override::Entity>,
+    #[sea_orm(
+        has_many,
+        relation_enum = "InspectionsPerformed",
+        via_rel = "Inspector"
+    )]
     pub inspections_performed: HasMany<super::vehicle_inspection::Entity>,
+    #[sea_orm(
+        has_many,
+        relation_enum = "InspectionsOverridden",
+        via_rel = "IncidentCommander"
+    )]
+    pub inspections_overridden: HasMany<super::vehicle_inspection::Entity>,
+    #[sea_orm(has_many)]
+    pub staff_roles: HasMany<super::staff_role::Entity>,
+    #[sea_orm(has_many)]
+    pub vehicle_reservations: HasMany<super::vehicle_reservation::Entity>,
+    #[sea_orm(has_many)]
+    pub vehicle_incidents: HasMany<super::vehicle_incident::Entity>,
+    #[sea_orm(has_many)]
+    pub assigned_deficiencies: HasMany<super::vehicle_deficiency::Entity>,
+    #[sea_orm(has_many, via = "flight_member")]
+    pub flights: HasMany<super::flight::Entity>,
+    #[sea_orm(has_many, via = "activity_staff")]
+    pub staffed_activities: HasMany<super::activity::Entity>,
+    #[sea_orm(has_many)]
+    pub schedule_reviews: HasMany<super::schedule_review::Entity>,
+    #[sea_orm(has_many)]
+    pub calendar_feeds: HasMany<super::calendar_feed::Entity>,
+    #[sea_orm(has_many)]
+    pub notifications: HasMany<super::notification::Entity>,
+    #[sea_orm(has_many)]
+    pub notification_reads: HasMany<super::notification_read::Entity>,
+    #[sea_orm(
+        has_many,
+        relation_enum = "FacilityRequestsMade",
+        via_rel = "Requester"
+    )]
+    pub facility_requests_made: HasMany<super::facility_request::Entity>,
+    #[sea_orm(
+        has_many,
+        relation_enum = "FacilityRequestsAssigned",
+        via_rel = "Assignee"
+    )]
+    pub facility_requests_assigned: HasMany<super::facility_request::Entity>,
+    #[sea_orm(has_many)]
+    pub facility_request_comments: HasMany<super::facility_request_comment::Entity>,
+    #[sea_orm(has_many)]
+    pub facility_status_changes: HasMany<super::facility_request_status::Entity>,
+    #[sea_orm(has_many)]
+    pub bed_assignments: HasMany<super::bed_assignment::Entity>,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -12,8 +12,13 @@ pub struct Model {
     pub name: String,
     pub location: String,
     pub created_at: DateTime,
+    #[sea_orm(unique)]
+    pub activity_uid: Option<Uuid>,
+    pub opens_at: Option<DateTime>,
     #[sea_orm(has_many, via = "headcount_entry")]
     pub attendees: HasMany<super::attendee::Entity>,
+    #[sea_orm(has_many, via = "headcount_flight")]
+    pub flights: HasMany<super::flight::Entity>,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
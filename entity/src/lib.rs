@@ -2,11 +2,47 @@
 
 pub mod prelude;
 
+pub mod activity;
+pub mod activity_flight;
+pub mod activity_staff;
 pub mod attendee;
+pub mod bed_assignment;
+pub mod building;
+pub mod calendar_feed;
+pub mod callsign;
+pub mod driver_override;
+pub mod facility_request;
+pub mod facility_request_comment;
+pub mod facility_request_photo;
+pub mod facility_request_status;
+pub mod flight;
+pub mod flight_member;
+pub mod hand_receipt;
 pub mod headcount;
 pub mod headcount_entry;
+pub mod headcount_flight;
+pub mod inspection_template;
+pub mod inspection_template_item;
+pub mod location;
+pub mod notice;
+pub mod notification;
+pub mod notification_read;
 pub mod radio;
+pub mod radio_ticket;
 pub mod radio_type;
+pub mod room;
+pub mod schedule;
+pub mod schedule_day;
+pub mod schedule_review;
+pub mod staff_role;
+pub mod time_block;
 pub mod vehicle;
+pub mod vehicle_deficiency;
+pub mod vehicle_incident;
+pub mod vehicle_incident_photo;
+pub mod vehicle_incident_witness;
 pub mod vehicle_inspection;
+pub mod vehicle_inspection_result;
+pub mod vehicle_reservation;
+pub mod vehicle_trip;
 pub mod vehicle_type;
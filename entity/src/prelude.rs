@@ -1,10 +1,46 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 2.0
 
+pub use super::activity::Entity as Activity;
+pub use super::activity_flight::Entity as ActivityFlight;
+pub use super::activity_staff::Entity as ActivityStaff;
 pub use super::attendee::Entity as Attendee;
+pub use super::bed_assignment::Entity as BedAssignment;
+pub use super::building::Entity as Building;
+pub use super::calendar_feed::Entity as CalendarFeed;
+pub use super::callsign::Entity as Callsign;
+pub use super::driver_override::Entity as DriverOverride;
+pub use super::facility_request::Entity as FacilityRequest;
+pub use super::facility_request_comment::Entity as FacilityRequestComment;
+pub use super::facility_request_photo::Entity as FacilityRequestPhoto;
+pub use super::facility_request_status::Entity as FacilityRequestStatus;
+pub use super::flight::Entity as Flight;
+pub use super::flight_member::Entity as FlightMember;
+pub use super::hand_receipt::Entity as HandReceipt;
 pub use super::headcount::Entity as Headcount;
 pub use super::headcount_entry::Entity as HeadcountEntry;
+pub use super::headcount_flight::Entity as HeadcountFlight;
+pub use super::inspection_template::Entity as InspectionTemplate;
+pub use super::inspection_template_item::Entity as InspectionTemplateItem;
+pub use super::location::Entity as Location;
+pub use super::notice::Entity as Notice;
+pub use super::notification::Entity as Notification;
+pub use super::notification_read::Entity as NotificationRead;
 pub use super::radio::Entity as Radio;
+pub use super::radio_ticket::Entity as RadioTicket;
 pub use super::radio_type::Entity as RadioType;
+pub use super::room::Entity as Room;
+pub use super::schedule::Entity as Schedule;
+pub use super::schedule_day::Entity as ScheduleDay;
+pub use super::schedule_review::Entity as ScheduleReview;
+pub use super::staff_role::Entity as StaffRole;
+pub use super::time_block::Entity as TimeBlock;
 pub use super::vehicle::Entity as Vehicle;
+pub use super::vehicle_deficiency::Entity as VehicleDeficiency;
+pub use super::vehicle_incident::Entity as VehicleIncident;
+pub use super::vehicle_incident_photo::Entity as VehicleIncidentPhoto;
+pub use super::vehicle_incident_witness::Entity as VehicleIncidentWitness;
 pub use super::vehicle_inspection::Entity as VehicleInspection;
+pub use super::vehicle_inspection_result::Entity as VehicleInspectionResult;
+pub use super::vehicle_reservation::Entity as VehicleReservation;
+pub use super::vehicle_trip::Entity as VehicleTrip;
 pub use super::vehicle_type::Entity as VehicleType;
@@ -10,6 +10,7 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub type_id: i32,
+    #[sea_orm(unique)]
     pub property_tag_number: String,
     pub owned_by_unit: String,
     pub issued_to: Option<i32>,
@@ -30,6 +31,10 @@ pub struct Model {
         on_delete = "NoAction"
     )]
     pub radio_type: HasOne<super::radio_type::Entity>,
+    #[sea_orm(has_many)]
+    pub hand_receipts: HasMany<super::hand_receipt::Entity>,
+    #[sea_orm(has_many)]
+    pub tickets: HasMany<super::radio_ticket::Entity>,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
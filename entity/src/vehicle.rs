@@ -14,6 +14,8 @@ pub struct Model {
     pub owned_by_unit: String,
     pub issued_to: Option<i32>,
     pub in_service: bool,
+    #[sea_orm(unique)]
+    pub property_tag_number: Option<String>,
     #[sea_orm(
         belongs_to,
         from = "issued_to",
@@ -32,6 +34,18 @@ pub struct Model {
         on_delete = "NoAction"
     )]
     pub vehicle_type: HasOne<super::vehicle_type::Entity>,
+    #[sea_orm(has_many)]
+    pub hand_receipts: HasMany<super::hand_receipt::Entity>,
+    #[sea_orm(has_many)]
+    pub trips: HasMany<super::vehicle_trip::Entity>,
+    #[sea_orm(has_many)]
+    pub driver_overrides: HasMany<super::driver_override::Entity>,
+    #[sea_orm(has_many)]
+    pub deficiencies: HasMany<super::vehicle_deficiency::Entity>,
+    #[sea_orm(has_many)]
+    pub reservations: HasMany<super::vehicle_reservation::Entity>,
+    #[sea_orm(has_many)]
+    pub incidents: HasMany<super::vehicle_incident::Entity>,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -11,116 +11,27 @@ pub struct Model {
     pub id: i32,
     pub started_at: DateTime,
     pub vehicle_id: i32,
-    pub windows: Option<bool>,
-    pub windows_remarks: Option<String>,
-    pub headlights: Option<bool>,
-    pub headlights_remarks: Option<String>,
-    pub tail_lights: Option<bool>,
-    pub tail_lights_remarks: Option<String>,
-    pub brake_lights: Option<bool>,
-    pub brake_lights_remarks: Option<String>,
-    pub turn_signals: Option<bool>,
-    pub turn_signals_remarks: Option<String>,
-    pub emergency_lights: Option<bool>,
-    pub emergency_lights_remarks: Option<String>,
-    pub license_plate_light: Option<bool>,
-    pub license_plate_light_remarks: Option<String>,
-    pub backup_light: Option<bool>,
-    pub backup_light_remarks: Option<String>,
-    pub backup_alarm: Option<bool>,
-    pub backup_alarm_remarks: Option<String>,
-    pub wiper_blades: Option<bool>,
-    pub wiper_blades_remarks: Option<String>,
-    pub horn: Option<bool>,
-    pub horn_remarks: Option<String>,
-    pub seats: Option<bool>,
-    pub seats_remarks: Option<String>,
-    pub restraints: Option<bool>,
-    pub restraints_remarks: Option<String>,
-    pub mirrors: Option<bool>,
-    pub mirrors_remarks: Option<String>,
-    pub beacon_light: Option<bool>,
-    pub beacon_light_remarks: Option<String>,
-    pub wiring: Option<bool>,
-    pub wiring_remarks: Option<String>,
-    pub brakes: Option<bool>,
-    pub brakes_remarks: Option<String>,
-    pub battery: Option<bool>,
-    pub battery_remarks: Option<String>,
-    pub brake_fluid: Option<bool>,
-    pub brake_fluid_remarks: Option<String>,
-    pub exhaust_system: Option<bool>,
-    pub exhaust_system_remarks: Option<String>,
-    pub oil_level: Option<bool>,
-    pub oil_level_remarks: Option<String>,
-    pub oil_last_change: Option<i32>,
-    pub coolant: Option<bool>,
-    pub coolant_remarks: Option<String>,
-    pub belts_hoses: Option<bool>,
-    pub belts_hoses_remarks: Option<String>,
-    pub transmission: Option<bool>,
-    pub transmission_remarks: Option<String>,
-    pub battery_cables: Option<bool>,
-    pub battery_cables_remarks: Option<String>,
-    pub air_filter: Option<bool>,
-    pub air_filter_remarks: Option<String>,
-    pub body: Option<bool>,
-    pub body_remarks: Option<String>,
-    pub paint: Option<bool>,
-    pub paint_remarks: Option<String>,
-    pub bumpers: Option<bool>,
-    pub bumpers_remarks: Option<String>,
-    pub tire_tread: Option<bool>,
-    pub tire_tread_remarks: Option<String>,
-    pub tire_gauge_present: Option<bool>,
-    pub front_recommended_pressure: Option<i32>,
-    pub rear_recommended_pressure: Option<i32>,
-    pub left_front_pressure: Option<i32>,
-    pub right_front_pressure: Option<i32>,
-    pub left_rear_pressure: Option<i32>,
-    pub right_rear_pressure: Option<i32>,
-    pub logbook_present: Option<bool>,
-    pub registration_present: Option<bool>,
-    pub registration_present_remarks: Option<String>,
-    pub registration_current: Option<bool>,
-    pub registration_current_remarks: Option<String>,
-    pub insurance_present: Option<bool>,
-    pub insurance_present_remarks: Option<String>,
-    pub insurance_current: Option<bool>,
-    pub insurance_current_remarks: Option<String>,
-    pub capf132_present: Option<bool>,
-    pub capf132_present_remarks: Option<String>,
-    pub capf132_current: Option<bool>,
-    pub capf132_current_remarks: Option<String>,
-    pub capf132_signed: Option<bool>,
-    pub capf132_signed_remarks: Option<String>,
-    pub first_aid_kit_present: Option<bool>,
-    pub first_aid_kit_present_remarks: Option<String>,
-    pub toolkit_present: Option<bool>,
-    pub toolkit_present_remarks: Option<String>,
-    pub toolkit_secured: Option<bool>,
-    pub toolkit_secured_remarks: Option<String>,
-    pub survival_kit_present: Option<bool>,
-    pub survival_kit_present_remarks: Option<String>,
-    pub survival_kit_current: Option<bool>,
+    pub template_id: Option<i32>,
     pub is_mission_ready: Option<bool>,
     pub inspector_capid: Option<i32>,
     pub signed_at: Option<DateTime>,
     #[sea_orm(column_name = "iccapid")]
     pub ic_capid: Option<i32>,
     pub ic_signed_at: Option<DateTime>,
-    // #[sea_orm(
-    //     belongs_to,
-    //     relation_enum = "IncidentCommnder",
-    //     from = "iccapid",
-    //     to = "capid",
-    //     on_update = "NoAction",
-    //     on_delete = "NoAction"
-    // )]
-    // pub incident_commander: HasOne<super::attendee::Entity>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub ic_justification: Option<String>,
     #[sea_orm(
         belongs_to,
-        // relation_enum = "Inspector",
+        relation_enum = "IncidentCommander",
+        from = "ic_capid",
+        to = "capid",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    pub incident_commander: HasOne<super::attendee::Entity>,
+    #[sea_orm(
+        belongs_to,
+        relation_enum = "Inspector",
         from = "inspector_capid",
         to = "capid",
         on_update = "NoAction",
@@ -135,6 +46,18 @@ pub struct Model {
         on_delete = "NoAction"
     )]
     pub vehicle: HasOne<super::vehicle::Entity>,
+    #[sea_orm(
+        belongs_to,
+        from = "template_id",
+        to = "id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    pub template: HasOne<super::inspection_template::Entity>,
+    #[sea_orm(has_many)]
+    pub results: HasMany<super::vehicle_inspection_result::Entity>,
+    #[sea_orm(has_many)]
+    pub deficiencies: HasMany<super::vehicle_deficiency::Entity>,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -4,6 +4,28 @@ mod m20260108_142456_create_table;
 mod m20260120_050615_create_headcount_table;
 mod m20260125_124704_create_radio_table;
 mod m20260127_184142_create_vehicle_table;
+mod m20260202_173019_create_callsign_table;
+mod m20260205_091544_add_property_tag_constraints;
+mod m20260209_140327_create_hand_receipt_table;
+mod m20260211_162208_add_attendee_departed_at;
+mod m20260213_103512_create_radio_ticket_table;
+mod m20260216_083047_create_vehicle_trip_table;
+mod m20260218_141930_add_driver_qualification;
+mod m20260223_100412_create_inspection_templates;
+mod m20260226_152208_create_vehicle_deficiency_table;
+mod m20260302_094518_add_inspection_override_roles;
+mod m20260305_110734_create_vehicle_reservation_table;
+mod m20260309_143026_create_vehicle_incident_tables;
+mod m20260312_090514_create_schedule_tables;
+mod m20260316_101522_add_schedule_approval;
+mod m20260319_150241_create_calendar_feed_table;
+mod m20260323_081245_add_activity_headcounts;
+mod m20260326_143318_create_notification_table;
+mod m20260330_191507_add_plan_of_the_day;
+mod m20260402_104933_create_facility_request_tables;
+mod m20260406_132816_create_lodging_tables;
+mod m20260409_101744_add_incident_clearance;
+mod m20260411_084217_create_notification_read_table;
 
 pub struct Migrator;
 
@@ -15,6 +37,28 @@ impl MigratorTrait for Migrator {
             Box::new(m20260120_050615_create_headcount_table::Migration),
             Box::new(m20260125_124704_create_radio_table::Migration),
             Box::new(m20260127_184142_create_vehicle_table::Migration),
+            Box::new(m20260202_173019_create_callsign_table::Migration),
+            Box::new(m20260205_091544_add_property_tag_constraints::Migration),
+            Box::new(m20260209_140327_create_hand_receipt_table::Migration),
+            Box::new(m20260211_162208_add_attendee_departed_at::Migration),
+            Box::new(m20260213_103512_create_radio_ticket_table::Migration),
+            Box::new(m20260216_083047_create_vehicle_trip_table::Migration),
+            Box::new(m20260218_141930_add_driver_qualification::Migration),
+            Box::new(m20260223_100412_create_inspection_templates::Migration),
+            Box::new(m20260226_152208_create_vehicle_deficiency_table::Migration),
+            Box::new(m20260302_094518_add_inspection_override_roles::Migration),
+            Box::new(m20260305_110734_create_vehicle_reservation_table::Migration),
+            Box::new(m20260309_143026_create_vehicle_incident_tables::Migration),
+            Box::new(m20260312_090514_create_schedule_tables::Migration),
+            Box::new(m20260316_101522_add_schedule_approval::Migration),
+            Box::new(m20260319_150241_create_calendar_feed_table::Migration),
+            Box::new(m20260323_081245_add_activity_headcounts::Migration),
+            Box::new(m20260326_143318_create_notification_table::Migration),
+            Box::new(m20260330_191507_add_plan_of_the_day::Migration),
+            Box::new(m20260402_104933_create_facility_request_tables::Migration),
+            Box::new(m20260406_132816_create_lodging_tables::Migration),
+            Box::new(m20260409_101744_add_incident_clearance::Migration),
+            Box::new(m20260411_084217_create_notification_read_table::Migration),
         ]
     }
 }
@@ -84,6 +84,7 @@ impl MigrationTrait for Migration {
 }
 
 #[derive(DeriveIden)]
+#[allow(clippy::upper_case_acronyms)]
 pub(crate) enum Attendee {
     Table,
     CAPID,
@@ -146,4 +147,6 @@ pub(crate) enum Attendee {
     PricesID,
     InvoiceStatus,
     RegisteredBy,
+    DepartedAt,
+    CAPLicenseExpiration,
 }
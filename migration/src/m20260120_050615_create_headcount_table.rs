@@ -84,15 +84,18 @@ impl MigrationTrait for Migration {
 }
 
 #[derive(DeriveIden)]
-enum Headcount {
+pub(crate) enum Headcount {
     Table,
     Id,
     Name,
     Location,
     CreatedAt,
+    ActivityUid,
+    OpensAt,
 }
 
 #[derive(DeriveIden)]
+#[allow(clippy::upper_case_acronyms)]
 enum HeadcountEntry {
     Table,
     Id,
@@ -68,7 +68,7 @@ enum RadioType {
 }
 
 #[derive(DeriveIden)]
-enum Radio {
+pub(crate) enum Radio {
     Table,
     Id,
     TypeId,
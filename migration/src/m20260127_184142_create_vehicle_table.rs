@@ -216,7 +216,7 @@ enum VehicleType {
 }
 
 #[derive(DeriveIden)]
-enum Vehicle {
+pub(crate) enum Vehicle {
     Table,
     Id,
     TypeId,
@@ -224,10 +224,12 @@ enum Vehicle {
     OwnedByUnit,
     IssuedTo,
     InService,
+    PropertyTagNumber,
 }
 
 #[derive(DeriveIden)]
-enum VehicleInspection {
+#[allow(clippy::upper_case_acronyms)]
+pub(crate) enum VehicleInspection {
     Table,
     Id,
     StartedAt,
@@ -329,4 +331,6 @@ enum VehicleInspection {
     SignedAt,
     ICCAPID,
     ICSignedAt,
+    TemplateId,
+    ICJustification,
 }
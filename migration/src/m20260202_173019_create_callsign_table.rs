@@ -0,0 +1,45 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Callsign::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Callsign::Id))
+                    .col(string_uniq(Callsign::Name))
+                    .col(string_null(Callsign::Position))
+                    .col(integer_null(Callsign::AssignedTo))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-callsign-assigned-to-capid")
+                            .from(Callsign::Table, Callsign::AssignedTo)
+                            .to(Attendee::Table, Attendee::CAPID),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().if_exists().table(Callsign::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Callsign {
+    Table,
+    Id,
+    Name,
+    Position,
+    AssignedTo,
+}
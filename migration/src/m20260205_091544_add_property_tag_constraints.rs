@@ -0,0 +1,97 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260125_124704_create_radio_table::Radio;
+use crate::m20260127_184142_create_vehicle_table::Vehicle;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Tags were free text until now, so repeats have to be sorted out by
+        // hand before the index can go on
+        let duplicates = manager
+            .get_connection()
+            .query_all(
+                &Query::select()
+                    .column(Radio::PropertyTagNumber)
+                    .from(Radio::Table)
+                    .group_by_col(Radio::PropertyTagNumber)
+                    .and_having(Expr::col(Radio::PropertyTagNumber).count().gt(1))
+                    .order_by(Radio::PropertyTagNumber, Order::Asc)
+                    .to_owned(),
+            )
+            .await?
+            .into_iter()
+            .map(|row| row.try_get::<String>("", "property_tag_number"))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !duplicates.is_empty() {
+            return Err(DbErr::Migration(format!(
+                "Radio property tags are used more than once and must be made unique first: {}",
+                duplicates.join(", ")
+            )));
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-radio-property-tag-number")
+                    .table(Radio::Table)
+                    .col(Radio::PropertyTagNumber)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Vehicle::Table)
+                    .add_column(string_null(Vehicle::PropertyTagNumber))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-vehicle-property-tag-number")
+                    .table(Vehicle::Table)
+                    .col(Vehicle::PropertyTagNumber)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .name("idx-vehicle-property-tag-number")
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Vehicle::Table)
+                    .drop_column(Vehicle::PropertyTagNumber)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .name("idx-radio-property-tag-number")
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,84 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+use crate::m20260125_124704_create_radio_table::Radio;
+use crate::m20260127_184142_create_vehicle_table::Vehicle;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(HandReceipt::Table)
+                    .if_not_exists()
+                    .col(pk_auto(HandReceipt::Id))
+                    .col(integer_null(HandReceipt::RadioId))
+                    .col(integer_null(HandReceipt::VehicleId))
+                    .col(integer(HandReceipt::IssuedTo))
+                    .col(integer_null(HandReceipt::IssuedBy))
+                    .col(string(HandReceipt::Condition))
+                    .col(date_time(HandReceipt::IssuedAt))
+                    .col(string_null(HandReceipt::SignedName))
+                    .col(string_null(HandReceipt::SignatureFile))
+                    .col(date_time_null(HandReceipt::SignedAt))
+                    .col(date_time_null(HandReceipt::ReturnedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-hand-receipt-radio")
+                            .from(HandReceipt::Table, HandReceipt::RadioId)
+                            .to(Radio::Table, Radio::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-hand-receipt-vehicle")
+                            .from(HandReceipt::Table, HandReceipt::VehicleId)
+                            .to(Vehicle::Table, Vehicle::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-hand-receipt-issued-to-capid")
+                            .from(HandReceipt::Table, HandReceipt::IssuedTo)
+                            .to(Attendee::Table, Attendee::CAPID),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-hand-receipt-issued-by-capid")
+                            .from(HandReceipt::Table, HandReceipt::IssuedBy)
+                            .to(Attendee::Table, Attendee::CAPID),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(HandReceipt::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum HandReceipt {
+    Table,
+    Id,
+    RadioId,
+    VehicleId,
+    IssuedTo,
+    IssuedBy,
+    Condition,
+    IssuedAt,
+    SignedName,
+    SignatureFile,
+    SignedAt,
+    ReturnedAt,
+}
@@ -0,0 +1,73 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+use crate::m20260125_124704_create_radio_table::Radio;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RadioTicket::Table)
+                    .if_not_exists()
+                    .col(pk_auto(RadioTicket::Id))
+                    .col(integer(RadioTicket::RadioId))
+                    .col(text(RadioTicket::Problem))
+                    .col(integer_null(RadioTicket::ReportedBy))
+                    .col(date_time(RadioTicket::ReportedAt))
+                    .col(string(RadioTicket::Status))
+                    .col(text_null(RadioTicket::RepairActions))
+                    .col(integer_null(RadioTicket::ReturnedBy))
+                    .col(date_time_null(RadioTicket::ReturnedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-radio-ticket-radio")
+                            .from(RadioTicket::Table, RadioTicket::RadioId)
+                            .to(Radio::Table, Radio::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-radio-ticket-reported-by-capid")
+                            .from(RadioTicket::Table, RadioTicket::ReportedBy)
+                            .to(Attendee::Table, Attendee::CAPID),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-radio-ticket-returned-by-capid")
+                            .from(RadioTicket::Table, RadioTicket::ReturnedBy)
+                            .to(Attendee::Table, Attendee::CAPID),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(RadioTicket::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RadioTicket {
+    Table,
+    Id,
+    RadioId,
+    Problem,
+    ReportedBy,
+    ReportedAt,
+    Status,
+    RepairActions,
+    ReturnedBy,
+    ReturnedAt,
+}
@@ -0,0 +1,69 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+use crate::m20260127_184142_create_vehicle_table::Vehicle;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(VehicleTrip::Table)
+                    .if_not_exists()
+                    .col(pk_auto(VehicleTrip::Id))
+                    .col(integer(VehicleTrip::VehicleId))
+                    .col(integer(VehicleTrip::DriverCapid))
+                    .col(string(VehicleTrip::Purpose))
+                    .col(string(VehicleTrip::Destination))
+                    .col(integer(VehicleTrip::StartOdometer))
+                    .col(integer_null(VehicleTrip::EndOdometer))
+                    .col(date_time(VehicleTrip::DepartedAt))
+                    .col(date_time_null(VehicleTrip::ReturnedAt))
+                    .col(double_null(VehicleTrip::FuelAdded))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-vehicle-trip-vehicle")
+                            .from(VehicleTrip::Table, VehicleTrip::VehicleId)
+                            .to(Vehicle::Table, Vehicle::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-vehicle-trip-driver-capid")
+                            .from(VehicleTrip::Table, VehicleTrip::DriverCapid)
+                            .to(Attendee::Table, Attendee::CAPID),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(VehicleTrip::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub(crate) enum VehicleTrip {
+    Table,
+    Id,
+    VehicleId,
+    DriverCapid,
+    Purpose,
+    Destination,
+    StartOdometer,
+    EndOdometer,
+    DepartedAt,
+    ReturnedAt,
+    FuelAdded,
+}
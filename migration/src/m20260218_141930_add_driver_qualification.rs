@@ -0,0 +1,87 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+use crate::m20260127_184142_create_vehicle_table::Vehicle;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Attendee::Table)
+                    .add_column(date_null(Attendee::CAPLicenseExpiration))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(DriverOverride::Table)
+                    .if_not_exists()
+                    .col(pk_auto(DriverOverride::Id))
+                    .col(integer(DriverOverride::VehicleId))
+                    .col(integer(DriverOverride::DriverCapid))
+                    .col(integer(DriverOverride::ApprovedBy))
+                    .col(text(DriverOverride::Reasons))
+                    .col(text(DriverOverride::Justification))
+                    .col(date_time(DriverOverride::CreatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-driver-override-vehicle")
+                            .from(DriverOverride::Table, DriverOverride::VehicleId)
+                            .to(Vehicle::Table, Vehicle::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-driver-override-driver-capid")
+                            .from(DriverOverride::Table, DriverOverride::DriverCapid)
+                            .to(Attendee::Table, Attendee::CAPID),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-driver-override-approved-by-capid")
+                            .from(DriverOverride::Table, DriverOverride::ApprovedBy)
+                            .to(Attendee::Table, Attendee::CAPID),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(DriverOverride::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Attendee::Table)
+                    .drop_column(Attendee::CAPLicenseExpiration)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DriverOverride {
+    Table,
+    Id,
+    VehicleId,
+    DriverCapid,
+    ApprovedBy,
+    Reasons,
+    Justification,
+    CreatedAt,
+}
@@ -0,0 +1,488 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260127_184142_create_vehicle_table::VehicleInspection;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// The checklist as it was hard-coded into vehicle_inspection, in form order:
+// key, section, label, result type, whether the old table had a remarks column
+const ITEMS: &[(&str, &str, &str, &str, bool)] = &[
+    ("windows", "Cab", "Windows", "pass_fail", true),
+    ("headlights", "Lights", "Headlights", "pass_fail", true),
+    ("tail_lights", "Lights", "Tail lights", "pass_fail", true),
+    ("brake_lights", "Lights", "Brake lights", "pass_fail", true),
+    ("turn_signals", "Lights", "Turn signals", "pass_fail", true),
+    (
+        "emergency_lights",
+        "Lights",
+        "Emergency flashers",
+        "pass_fail",
+        true,
+    ),
+    (
+        "license_plate_light",
+        "Lights",
+        "License plate light",
+        "pass_fail",
+        true,
+    ),
+    ("backup_light", "Lights", "Backup light", "pass_fail", true),
+    ("backup_alarm", "Lights", "Backup alarm", "pass_fail", true),
+    ("wiper_blades", "Cab", "Wiper blades", "pass_fail", true),
+    ("horn", "Cab", "Horn", "pass_fail", true),
+    ("seats", "Cab", "Seats", "pass_fail", true),
+    (
+        "restraints",
+        "Cab",
+        "Seat belts and restraints",
+        "pass_fail",
+        true,
+    ),
+    ("mirrors", "Cab", "Mirrors", "pass_fail", true),
+    ("beacon_light", "Lights", "Beacon light", "pass_fail", true),
+    ("wiring", "Engine", "Wiring", "pass_fail", true),
+    ("brakes", "Engine", "Brakes", "pass_fail", true),
+    ("battery", "Engine", "Battery", "pass_fail", true),
+    ("brake_fluid", "Engine", "Brake fluid", "pass_fail", true),
+    (
+        "exhaust_system",
+        "Engine",
+        "Exhaust system",
+        "pass_fail",
+        true,
+    ),
+    ("oil_level", "Engine", "Oil level", "pass_fail", true),
+    (
+        "oil_last_change",
+        "Engine",
+        "Mileage at last oil change",
+        "number",
+        false,
+    ),
+    ("coolant", "Engine", "Coolant", "pass_fail", true),
+    (
+        "belts_hoses",
+        "Engine",
+        "Belts and hoses",
+        "pass_fail",
+        true,
+    ),
+    (
+        "transmission",
+        "Engine",
+        "Transmission fluid",
+        "pass_fail",
+        true,
+    ),
+    (
+        "battery_cables",
+        "Engine",
+        "Battery cables",
+        "pass_fail",
+        true,
+    ),
+    ("air_filter", "Engine", "Air filter", "pass_fail", true),
+    ("body", "Body", "Body", "pass_fail", true),
+    ("paint", "Body", "Paint", "pass_fail", true),
+    ("bumpers", "Body", "Bumpers", "pass_fail", true),
+    ("tire_tread", "Tires", "Tire tread", "pass_fail", true),
+    (
+        "tire_gauge_present",
+        "Tires",
+        "Tire gauge present",
+        "pass_fail",
+        false,
+    ),
+    (
+        "front_recommended_pressure",
+        "Tires",
+        "Front recommended pressure",
+        "number",
+        false,
+    ),
+    (
+        "rear_recommended_pressure",
+        "Tires",
+        "Rear recommended pressure",
+        "number",
+        false,
+    ),
+    (
+        "left_front_pressure",
+        "Tires",
+        "Left front pressure",
+        "number",
+        false,
+    ),
+    (
+        "right_front_pressure",
+        "Tires",
+        "Right front pressure",
+        "number",
+        false,
+    ),
+    (
+        "left_rear_pressure",
+        "Tires",
+        "Left rear pressure",
+        "number",
+        false,
+    ),
+    (
+        "right_rear_pressure",
+        "Tires",
+        "Right rear pressure",
+        "number",
+        false,
+    ),
+    (
+        "logbook_present",
+        "Documents",
+        "Logbook present",
+        "pass_fail",
+        false,
+    ),
+    (
+        "registration_present",
+        "Documents",
+        "Registration present",
+        "pass_fail",
+        true,
+    ),
+    (
+        "registration_current",
+        "Documents",
+        "Registration current",
+        "pass_fail",
+        true,
+    ),
+    (
+        "insurance_present",
+        "Documents",
+        "Insurance card present",
+        "pass_fail",
+        true,
+    ),
+    (
+        "insurance_current",
+        "Documents",
+        "Insurance card current",
+        "pass_fail",
+        true,
+    ),
+    (
+        "capf132_present",
+        "Documents",
+        "CAPF 132 present",
+        "pass_fail",
+        true,
+    ),
+    (
+        "capf132_current",
+        "Documents",
+        "CAPF 132 current",
+        "pass_fail",
+        true,
+    ),
+    (
+        "capf132_signed",
+        "Documents",
+        "CAPF 132 signed",
+        "pass_fail",
+        true,
+    ),
+    (
+        "first_aid_kit_present",
+        "Equipment",
+        "First aid kit present",
+        "pass_fail",
+        true,
+    ),
+    (
+        "toolkit_present",
+        "Equipment",
+        "Toolkit present",
+        "pass_fail",
+        true,
+    ),
+    (
+        "toolkit_secured",
+        "Equipment",
+        "Toolkit secured",
+        "pass_fail",
+        true,
+    ),
+    (
+        "survival_kit_present",
+        "Equipment",
+        "Survival kit present",
+        "pass_fail",
+        true,
+    ),
+    (
+        "survival_kit_current",
+        "Equipment",
+        "Survival kit current",
+        "pass_fail",
+        false,
+    ),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(InspectionTemplate::Table)
+                    .if_not_exists()
+                    .col(pk_auto(InspectionTemplate::Id))
+                    .col(integer_uniq(InspectionTemplate::Version))
+                    .col(date_time(InspectionTemplate::CreatedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(InspectionTemplateItem::Table)
+                    .if_not_exists()
+                    .col(pk_auto(InspectionTemplateItem::Id))
+                    .col(integer(InspectionTemplateItem::TemplateId))
+                    .col(integer(InspectionTemplateItem::Position))
+                    .col(string(InspectionTemplateItem::Key))
+                    .col(string(InspectionTemplateItem::Section))
+                    .col(string(InspectionTemplateItem::Label))
+                    .col(string(InspectionTemplateItem::ResultType))
+                    .col(boolean(InspectionTemplateItem::RemarksRequired))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-inspection-template-item-template")
+                            .from(
+                                InspectionTemplateItem::Table,
+                                InspectionTemplateItem::TemplateId,
+                            )
+                            .to(InspectionTemplate::Table, InspectionTemplate::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx-inspection-template-item-key")
+                            .col(InspectionTemplateItem::TemplateId)
+                            .col(InspectionTemplateItem::Key)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(VehicleInspectionResult::Table)
+                    .if_not_exists()
+                    .col(pk_auto(VehicleInspectionResult::Id))
+                    .col(integer(VehicleInspectionResult::InspectionId))
+                    .col(string(VehicleInspectionResult::ItemKey))
+                    .col(boolean_null(VehicleInspectionResult::Passed))
+                    .col(integer_null(VehicleInspectionResult::Value))
+                    .col(string_null(VehicleInspectionResult::Remarks))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-vehicle-inspection-result-inspection")
+                            .from(
+                                VehicleInspectionResult::Table,
+                                VehicleInspectionResult::InspectionId,
+                            )
+                            .to(VehicleInspection::Table, VehicleInspection::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx-vehicle-inspection-result-item")
+                            .col(VehicleInspectionResult::InspectionId)
+                            .col(VehicleInspectionResult::ItemKey)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(VehicleInspection::Table)
+                    .add_column(integer_null(VehicleInspection::TemplateId))
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk-vehicle-inspection-template")
+                            .from_tbl(VehicleInspection::Table)
+                            .from_col(VehicleInspection::TemplateId)
+                            .to_tbl(InspectionTemplate::Table)
+                            .to_col(InspectionTemplate::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            "INSERT INTO inspection_template (version, created_at) VALUES (1, CURRENT_TIMESTAMP)",
+        )
+        .await?;
+
+        for (position, (key, section, label, result_type, has_remarks)) in ITEMS.iter().enumerate()
+        {
+            db.execute_unprepared(&format!(
+                "INSERT INTO inspection_template_item \
+                 (template_id, position, key, section, label, result_type, remarks_required) \
+                 SELECT id, {position}, '{key}', '{section}', '{label}', '{result_type}', {has_remarks} \
+                 FROM inspection_template WHERE version = 1"
+            ))
+            .await?;
+
+            let remarks = format!("{key}_remarks");
+            let (passed, value, remarks) = match (*result_type, has_remarks) {
+                ("number", _) => ("NULL", *key, "NULL"),
+                (_, true) => (*key, "NULL", remarks.as_str()),
+                (_, false) => (*key, "NULL", "NULL"),
+            };
+
+            db.execute_unprepared(&format!(
+                "INSERT INTO vehicle_inspection_result \
+                 (inspection_id, item_key, passed, value, remarks) \
+                 SELECT id, '{key}', {passed}, {value}, {remarks} FROM vehicle_inspection \
+                 WHERE {passed} IS NOT NULL OR {value} IS NOT NULL OR {remarks} IS NOT NULL"
+            ))
+            .await?;
+        }
+
+        db.execute_unprepared(
+            "UPDATE vehicle_inspection SET template_id = \
+             (SELECT id FROM inspection_template WHERE version = 1)",
+        )
+        .await?;
+
+        let mut alter = Table::alter();
+        alter.table(VehicleInspection::Table);
+        for (key, _, _, _, has_remarks) in ITEMS {
+            alter.drop_column(Alias::new(*key));
+            if *has_remarks {
+                alter.drop_column(Alias::new(format!("{key}_remarks")));
+            }
+        }
+
+        manager.alter_table(alter.to_owned()).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let mut alter = Table::alter();
+        alter.table(VehicleInspection::Table);
+        for (key, _, _, result_type, has_remarks) in ITEMS {
+            if *result_type == "number" {
+                alter.add_column(integer_null(Alias::new(*key)));
+            } else {
+                alter.add_column(boolean_null(Alias::new(*key)));
+            }
+            if *has_remarks {
+                alter.add_column(string_null(Alias::new(format!("{key}_remarks"))));
+            }
+        }
+
+        manager.alter_table(alter.to_owned()).await?;
+
+        let db = manager.get_connection();
+
+        for (key, _, _, result_type, has_remarks) in ITEMS {
+            let source = if *result_type == "number" {
+                "value"
+            } else {
+                "passed"
+            };
+
+            let mut sets = format!("{key} = r.{source}");
+            if *has_remarks {
+                sets.push_str(&format!(", {key}_remarks = r.remarks"));
+            }
+
+            db.execute_unprepared(&format!(
+                "UPDATE vehicle_inspection SET {sets} FROM vehicle_inspection_result r \
+                 WHERE r.inspection_id = vehicle_inspection.id AND r.item_key = '{key}'"
+            ))
+            .await?;
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(VehicleInspection::Table)
+                    .drop_foreign_key(Alias::new("fk-vehicle-inspection-template"))
+                    .drop_column(VehicleInspection::TemplateId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(VehicleInspectionResult::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(InspectionTemplateItem::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(InspectionTemplate::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum InspectionTemplate {
+    Table,
+    Id,
+    Version,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub(crate) enum InspectionTemplateItem {
+    Table,
+    Id,
+    TemplateId,
+    Position,
+    Key,
+    Section,
+    Label,
+    ResultType,
+    RemarksRequired,
+    Severity,
+}
+
+#[derive(DeriveIden)]
+enum VehicleInspectionResult {
+    Table,
+    Id,
+    InspectionId,
+    ItemKey,
+    Passed,
+    Value,
+    Remarks,
+}
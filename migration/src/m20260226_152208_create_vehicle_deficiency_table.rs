@@ -0,0 +1,123 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+use crate::m20260127_184142_create_vehicle_table::{Vehicle, VehicleInspection};
+use crate::m20260223_100412_create_inspection_templates::InspectionTemplateItem;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// Failures that take a vehicle off the road until they are fixed
+const GROUNDING: &[&str] = &[
+    "headlights",
+    "brake_lights",
+    "turn_signals",
+    "restraints",
+    "brakes",
+    "brake_fluid",
+    "tire_tread",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(InspectionTemplateItem::Table)
+                    .add_column(string(InspectionTemplateItem::Severity).default("minor"))
+                    .to_owned(),
+            )
+            .await?;
+
+        let keys: Vec<String> = GROUNDING.iter().map(|key| format!("'{key}'")).collect();
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "UPDATE inspection_template_item SET severity = 'grounding' WHERE key IN ({})",
+                keys.join(", ")
+            ))
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(VehicleDeficiency::Table)
+                    .if_not_exists()
+                    .col(pk_auto(VehicleDeficiency::Id))
+                    .col(integer(VehicleDeficiency::VehicleId))
+                    .col(integer_null(VehicleDeficiency::InspectionId))
+                    .col(string(VehicleDeficiency::ItemKey))
+                    .col(text(VehicleDeficiency::Description))
+                    .col(string(VehicleDeficiency::Severity))
+                    .col(integer_null(VehicleDeficiency::AssignedTo))
+                    .col(date_time(VehicleDeficiency::OpenedAt))
+                    .col(text_null(VehicleDeficiency::Resolution))
+                    .col(integer_null(VehicleDeficiency::ClosedBy))
+                    .col(date_time_null(VehicleDeficiency::ClosedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-vehicle-deficiency-vehicle")
+                            .from(VehicleDeficiency::Table, VehicleDeficiency::VehicleId)
+                            .to(Vehicle::Table, Vehicle::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-vehicle-deficiency-inspection")
+                            .from(VehicleDeficiency::Table, VehicleDeficiency::InspectionId)
+                            .to(VehicleInspection::Table, VehicleInspection::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-vehicle-deficiency-assigned-to-capid")
+                            .from(VehicleDeficiency::Table, VehicleDeficiency::AssignedTo)
+                            .to(Attendee::Table, Attendee::CAPID),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-vehicle-deficiency-closed-by-capid")
+                            .from(VehicleDeficiency::Table, VehicleDeficiency::ClosedBy)
+                            .to(Attendee::Table, Attendee::CAPID),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(VehicleDeficiency::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(InspectionTemplateItem::Table)
+                    .drop_column(InspectionTemplateItem::Severity)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum VehicleDeficiency {
+    Table,
+    Id,
+    VehicleId,
+    InspectionId,
+    ItemKey,
+    Description,
+    Severity,
+    AssignedTo,
+    OpenedAt,
+    Resolution,
+    ClosedBy,
+    ClosedAt,
+}
@@ -0,0 +1,70 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+use crate::m20260127_184142_create_vehicle_table::VehicleInspection;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(VehicleInspection::Table)
+                    .add_column(text_null(VehicleInspection::ICJustification))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(StaffRole::Table)
+                    .if_not_exists()
+                    .col(pk_auto(StaffRole::Id))
+                    .col(integer(StaffRole::Capid))
+                    .col(string(StaffRole::Role))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-staff-role-capid")
+                            .from(StaffRole::Table, StaffRole::Capid)
+                            .to(Attendee::Table, Attendee::CAPID)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx-staff-role-capid-role")
+                            .col(StaffRole::Capid)
+                            .col(StaffRole::Role)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().if_exists().table(StaffRole::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(VehicleInspection::Table)
+                    .drop_column(VehicleInspection::ICJustification)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StaffRole {
+    Table,
+    Id,
+    Capid,
+    Role,
+}
@@ -0,0 +1,79 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+use crate::m20260127_184142_create_vehicle_table::Vehicle;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(VehicleReservation::Table)
+                    .if_not_exists()
+                    .col(pk_auto(VehicleReservation::Id))
+                    .col(integer(VehicleReservation::VehicleId))
+                    .col(integer(VehicleReservation::RequestedBy))
+                    .col(string(VehicleReservation::Purpose))
+                    .col(string(VehicleReservation::Destination))
+                    .col(integer(VehicleReservation::Passengers))
+                    .col(date_time(VehicleReservation::StartsAt))
+                    .col(date_time(VehicleReservation::EndsAt))
+                    .col(date_time(VehicleReservation::CreatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-vehicle-reservation-vehicle")
+                            .from(VehicleReservation::Table, VehicleReservation::VehicleId)
+                            .to(Vehicle::Table, Vehicle::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-vehicle-reservation-requested-by-capid")
+                            .from(VehicleReservation::Table, VehicleReservation::RequestedBy)
+                            .to(Attendee::Table, Attendee::CAPID),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-vehicle-reservation-window")
+                    .table(VehicleReservation::Table)
+                    .col(VehicleReservation::VehicleId)
+                    .col(VehicleReservation::StartsAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(VehicleReservation::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum VehicleReservation {
+    Table,
+    Id,
+    VehicleId,
+    RequestedBy,
+    Purpose,
+    Destination,
+    Passengers,
+    StartsAt,
+    EndsAt,
+    CreatedAt,
+}
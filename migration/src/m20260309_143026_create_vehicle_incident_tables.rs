@@ -0,0 +1,173 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+use crate::m20260127_184142_create_vehicle_table::Vehicle;
+use crate::m20260216_083047_create_vehicle_trip_table::VehicleTrip;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(VehicleIncident::Table)
+                    .if_not_exists()
+                    .col(pk_auto(VehicleIncident::Id))
+                    .col(integer(VehicleIncident::VehicleId))
+                    .col(integer_null(VehicleIncident::DriverCapid))
+                    .col(integer_null(VehicleIncident::TripId))
+                    .col(date_time(VehicleIncident::OccurredAt))
+                    .col(string(VehicleIncident::Location))
+                    .col(text(VehicleIncident::Damage))
+                    .col(text(VehicleIncident::Narrative))
+                    .col(integer(VehicleIncident::ReportedBy))
+                    .col(date_time(VehicleIncident::ReportedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-vehicle-incident-vehicle")
+                            .from(VehicleIncident::Table, VehicleIncident::VehicleId)
+                            .to(Vehicle::Table, Vehicle::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-vehicle-incident-driver-capid")
+                            .from(VehicleIncident::Table, VehicleIncident::DriverCapid)
+                            .to(Attendee::Table, Attendee::CAPID),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-vehicle-incident-trip")
+                            .from(VehicleIncident::Table, VehicleIncident::TripId)
+                            .to(VehicleTrip::Table, VehicleTrip::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-vehicle-incident-reported-by-capid")
+                            .from(VehicleIncident::Table, VehicleIncident::ReportedBy)
+                            .to(Attendee::Table, Attendee::CAPID),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(VehicleIncidentWitness::Table)
+                    .if_not_exists()
+                    .col(pk_auto(VehicleIncidentWitness::Id))
+                    .col(integer(VehicleIncidentWitness::IncidentId))
+                    .col(string(VehicleIncidentWitness::Name))
+                    .col(integer_null(VehicleIncidentWitness::Capid))
+                    .col(string_null(VehicleIncidentWitness::Phone))
+                    .col(text_null(VehicleIncidentWitness::Statement))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-vehicle-incident-witness-incident")
+                            .from(
+                                VehicleIncidentWitness::Table,
+                                VehicleIncidentWitness::IncidentId,
+                            )
+                            .to(VehicleIncident::Table, VehicleIncident::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(VehicleIncidentPhoto::Table)
+                    .if_not_exists()
+                    .col(pk_auto(VehicleIncidentPhoto::Id))
+                    .col(integer(VehicleIncidentPhoto::IncidentId))
+                    .col(string(VehicleIncidentPhoto::FileName))
+                    .col(string(VehicleIncidentPhoto::ContentType))
+                    .col(date_time(VehicleIncidentPhoto::UploadedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-vehicle-incident-photo-incident")
+                            .from(
+                                VehicleIncidentPhoto::Table,
+                                VehicleIncidentPhoto::IncidentId,
+                            )
+                            .to(VehicleIncident::Table, VehicleIncident::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(VehicleIncidentPhoto::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(VehicleIncidentWitness::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(VehicleIncident::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub(crate) enum VehicleIncident {
+    Table,
+    Id,
+    VehicleId,
+    DriverCapid,
+    TripId,
+    OccurredAt,
+    Location,
+    Damage,
+    Narrative,
+    ReportedBy,
+    ReportedAt,
+    ClearedBy,
+    ClearedAt,
+    Clearance,
+}
+
+#[derive(DeriveIden)]
+enum VehicleIncidentWitness {
+    Table,
+    Id,
+    IncidentId,
+    Name,
+    Capid,
+    Phone,
+    Statement,
+}
+
+#[derive(DeriveIden)]
+enum VehicleIncidentPhoto {
+    Table,
+    Id,
+    IncidentId,
+    FileName,
+    ContentType,
+    UploadedAt,
+}
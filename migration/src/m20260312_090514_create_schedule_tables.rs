@@ -0,0 +1,358 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Location::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Location::Id))
+                    .col(string_uniq(Location::Name))
+                    .col(integer_null(Location::Capacity))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Flight::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Flight::Id))
+                    .col(string_uniq(Flight::Name))
+                    .col(string(Flight::Squadron))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(FlightMember::Table)
+                    .if_not_exists()
+                    .col(pk_auto(FlightMember::Id))
+                    .col(integer(FlightMember::FlightId))
+                    .col(integer_uniq(FlightMember::Capid))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-flight-member-flight")
+                            .from(FlightMember::Table, FlightMember::FlightId)
+                            .to(Flight::Table, Flight::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-flight-member-capid")
+                            .from(FlightMember::Table, FlightMember::Capid)
+                            .to(Attendee::Table, Attendee::CAPID)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Schedule::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Schedule::Id))
+                    .col(string(Schedule::Name))
+                    .col(date_time(Schedule::CreatedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ScheduleDay::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ScheduleDay::Id))
+                    .col(integer(ScheduleDay::ScheduleId))
+                    .col(date(ScheduleDay::Date))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-schedule-day-schedule")
+                            .from(ScheduleDay::Table, ScheduleDay::ScheduleId)
+                            .to(Schedule::Table, Schedule::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx-schedule-day-schedule-date")
+                            .col(ScheduleDay::ScheduleId)
+                            .col(ScheduleDay::Date)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TimeBlock::Table)
+                    .if_not_exists()
+                    .col(pk_auto(TimeBlock::Id))
+                    .col(integer(TimeBlock::DayId))
+                    .col(string(TimeBlock::Name))
+                    .col(date_time(TimeBlock::StartsAt))
+                    .col(date_time(TimeBlock::EndsAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-time-block-day")
+                            .from(TimeBlock::Table, TimeBlock::DayId)
+                            .to(ScheduleDay::Table, ScheduleDay::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Activity::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Activity::Id))
+                    .col(integer(Activity::BlockId))
+                    .col(uuid(Activity::Uid))
+                    .col(string(Activity::Name))
+                    .col(text_null(Activity::Description))
+                    .col(integer_null(Activity::LocationId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-activity-block")
+                            .from(Activity::Table, Activity::BlockId)
+                            .to(TimeBlock::Table, TimeBlock::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-activity-location")
+                            .from(Activity::Table, Activity::LocationId)
+                            .to(Location::Table, Location::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ActivityFlight::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ActivityFlight::Id))
+                    .col(integer(ActivityFlight::ActivityId))
+                    .col(integer(ActivityFlight::FlightId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-activity-flight-activity")
+                            .from(ActivityFlight::Table, ActivityFlight::ActivityId)
+                            .to(Activity::Table, Activity::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-activity-flight-flight")
+                            .from(ActivityFlight::Table, ActivityFlight::FlightId)
+                            .to(Flight::Table, Flight::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx-activity-flight-activity-flight")
+                            .col(ActivityFlight::ActivityId)
+                            .col(ActivityFlight::FlightId)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ActivityStaff::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ActivityStaff::Id))
+                    .col(integer(ActivityStaff::ActivityId))
+                    .col(integer(ActivityStaff::Capid))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-activity-staff-activity")
+                            .from(ActivityStaff::Table, ActivityStaff::ActivityId)
+                            .to(Activity::Table, Activity::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-activity-staff-capid")
+                            .from(ActivityStaff::Table, ActivityStaff::Capid)
+                            .to(Attendee::Table, Attendee::CAPID)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx-activity-staff-activity-capid")
+                            .col(ActivityStaff::ActivityId)
+                            .col(ActivityStaff::Capid)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(ActivityStaff::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(ActivityFlight::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().if_exists().table(Activity::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().if_exists().table(TimeBlock::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(ScheduleDay::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().if_exists().table(Schedule::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(FlightMember::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().if_exists().table(Flight::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().if_exists().table(Location::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub(crate) enum Location {
+    Table,
+    Id,
+    Name,
+    Capacity,
+}
+
+#[derive(DeriveIden)]
+pub(crate) enum Flight {
+    Table,
+    Id,
+    Name,
+    Squadron,
+}
+
+#[derive(DeriveIden)]
+enum FlightMember {
+    Table,
+    Id,
+    FlightId,
+    Capid,
+}
+
+#[derive(DeriveIden)]
+pub(crate) enum Schedule {
+    Table,
+    Id,
+    Name,
+    CreatedAt,
+    SeriesId,
+    Version,
+    Status,
+    Stage,
+    PreviousVersionId,
+    PublishedAt,
+}
+
+#[derive(DeriveIden)]
+pub(crate) enum ScheduleDay {
+    Table,
+    Id,
+    ScheduleId,
+    Date,
+    Uniform,
+}
+
+#[derive(DeriveIden)]
+pub(crate) enum TimeBlock {
+    Table,
+    Id,
+    DayId,
+    Name,
+    StartsAt,
+    EndsAt,
+}
+
+#[derive(DeriveIden)]
+pub(crate) enum Activity {
+    Table,
+    Id,
+    BlockId,
+    Uid,
+    Name,
+    Description,
+    LocationId,
+    Headcount,
+}
+
+#[derive(DeriveIden)]
+enum ActivityFlight {
+    Table,
+    Id,
+    ActivityId,
+    FlightId,
+}
+
+#[derive(DeriveIden)]
+enum ActivityStaff {
+    Table,
+    Id,
+    ActivityId,
+    Capid,
+}
@@ -0,0 +1,123 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+use crate::m20260312_090514_create_schedule_tables::Schedule;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Schedule::Table)
+                    .add_column(uuid(Schedule::SeriesId).default(Expr::cust("gen_random_uuid()")))
+                    .add_column(integer(Schedule::Version).default(1))
+                    .add_column(string(Schedule::Status).default("draft"))
+                    .add_column(integer(Schedule::Stage).default(0))
+                    .add_column(integer_null(Schedule::PreviousVersionId))
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk-schedule-previous-version")
+                            .from_tbl(Schedule::Table)
+                            .from_col(Schedule::PreviousVersionId)
+                            .to_tbl(Schedule::Table)
+                            .to_col(Schedule::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-schedule-series-version")
+                    .table(Schedule::Table)
+                    .col(Schedule::SeriesId)
+                    .col(Schedule::Version)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ScheduleReview::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ScheduleReview::Id))
+                    .col(integer(ScheduleReview::ScheduleId))
+                    .col(integer(ScheduleReview::Stage))
+                    .col(string(ScheduleReview::Role))
+                    .col(integer(ScheduleReview::Capid))
+                    .col(string(ScheduleReview::Action))
+                    .col(text_null(ScheduleReview::Comments))
+                    .col(date_time(ScheduleReview::ActedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-schedule-review-schedule")
+                            .from(ScheduleReview::Table, ScheduleReview::ScheduleId)
+                            .to(Schedule::Table, Schedule::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-schedule-review-capid")
+                            .from(ScheduleReview::Table, ScheduleReview::Capid)
+                            .to(Attendee::Table, Attendee::CAPID),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(ScheduleReview::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-schedule-series-version")
+                    .table(Schedule::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Schedule::Table)
+                    .drop_foreign_key("fk-schedule-previous-version")
+                    .drop_column(Schedule::PreviousVersionId)
+                    .drop_column(Schedule::Stage)
+                    .drop_column(Schedule::Status)
+                    .drop_column(Schedule::Version)
+                    .drop_column(Schedule::SeriesId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ScheduleReview {
+    Table,
+    Id,
+    ScheduleId,
+    Stage,
+    Role,
+    Capid,
+    Action,
+    Comments,
+    ActedAt,
+}
@@ -0,0 +1,63 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+use crate::m20260312_090514_create_schedule_tables::Flight;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CalendarFeed::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CalendarFeed::Id))
+                    .col(uuid_uniq(CalendarFeed::Token))
+                    .col(string(CalendarFeed::Scope))
+                    .col(integer_null(CalendarFeed::FlightId))
+                    .col(integer_null(CalendarFeed::Capid))
+                    .col(date_time(CalendarFeed::CreatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-calendar-feed-flight")
+                            .from(CalendarFeed::Table, CalendarFeed::FlightId)
+                            .to(Flight::Table, Flight::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-calendar-feed-capid")
+                            .from(CalendarFeed::Table, CalendarFeed::Capid)
+                            .to(Attendee::Table, Attendee::CAPID)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(CalendarFeed::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CalendarFeed {
+    Table,
+    Id,
+    Token,
+    Scope,
+    FlightId,
+    Capid,
+    CreatedAt,
+}
@@ -0,0 +1,107 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260120_050615_create_headcount_table::Headcount;
+use crate::m20260312_090514_create_schedule_tables::{Activity, Flight};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Activity::Table)
+                    .add_column(boolean(Activity::Headcount).default(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Headcount::Table)
+                    .add_column(uuid_null(Headcount::ActivityUid).unique_key())
+                    .add_column(date_time_null(Headcount::OpensAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(HeadcountFlight::Table)
+                    .if_not_exists()
+                    .col(pk_auto(HeadcountFlight::Id))
+                    .col(integer(HeadcountFlight::HeadcountId))
+                    .col(integer(HeadcountFlight::FlightId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-headcount-flight-headcount")
+                            .from(HeadcountFlight::Table, HeadcountFlight::HeadcountId)
+                            .to(Headcount::Table, Headcount::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-headcount-flight-flight")
+                            .from(HeadcountFlight::Table, HeadcountFlight::FlightId)
+                            .to(Flight::Table, Flight::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-headcount-flight-pair")
+                    .table(HeadcountFlight::Table)
+                    .col(HeadcountFlight::HeadcountId)
+                    .col(HeadcountFlight::FlightId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(HeadcountFlight::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Headcount::Table)
+                    .drop_column(Headcount::ActivityUid)
+                    .drop_column(Headcount::OpensAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Activity::Table)
+                    .drop_column(Activity::Headcount)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum HeadcountFlight {
+    Table,
+    Id,
+    HeadcountId,
+    FlightId,
+}
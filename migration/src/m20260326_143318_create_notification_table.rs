@@ -0,0 +1,103 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+use crate::m20260312_090514_create_schedule_tables::{Flight, Schedule};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Schedule::Table)
+                    .add_column(date_time_null(Schedule::PublishedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Versions approved before this have no record of when, so call it
+        // when they were drafted
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE schedule SET published_at = created_at WHERE status = 'approved'",
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Notification::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Notification::Id))
+                    .col(integer_null(Notification::Capid))
+                    .col(integer_null(Notification::FlightId))
+                    .col(integer(Notification::ScheduleId))
+                    .col(uuid(Notification::ActivityUid))
+                    .col(string(Notification::Kind))
+                    .col(text(Notification::Message))
+                    .col(date_time(Notification::CreatedAt))
+                    .col(date_time_null(Notification::ReadAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notification-capid")
+                            .from(Notification::Table, Notification::Capid)
+                            .to(Attendee::Table, Attendee::CAPID)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notification-flight")
+                            .from(Notification::Table, Notification::FlightId)
+                            .to(Flight::Table, Flight::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notification-schedule")
+                            .from(Notification::Table, Notification::ScheduleId)
+                            .to(Schedule::Table, Schedule::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(Notification::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Schedule::Table)
+                    .drop_column(Schedule::PublishedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub(crate) enum Notification {
+    Table,
+    Id,
+    Capid,
+    FlightId,
+    ScheduleId,
+    ActivityUid,
+    Kind,
+    Message,
+    CreatedAt,
+    ReadAt,
+}
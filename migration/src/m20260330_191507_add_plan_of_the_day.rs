@@ -0,0 +1,59 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260312_090514_create_schedule_tables::ScheduleDay;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ScheduleDay::Table)
+                    .add_column(string_null(ScheduleDay::Uniform))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Notice::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Notice::Id))
+                    .col(date(Notice::Date))
+                    .col(string(Notice::Title))
+                    .col(text(Notice::Body))
+                    .col(date_time(Notice::CreatedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().if_exists().table(Notice::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ScheduleDay::Table)
+                    .drop_column(ScheduleDay::Uniform)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Notice {
+    Table,
+    Id,
+    Date,
+    Title,
+    Body,
+    CreatedAt,
+}
@@ -0,0 +1,223 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+use crate::m20260312_090514_create_schedule_tables::Location;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(FacilityRequest::Table)
+                    .if_not_exists()
+                    .col(pk_auto(FacilityRequest::Id))
+                    .col(string(FacilityRequest::Category))
+                    .col(integer_null(FacilityRequest::LocationId))
+                    .col(text(FacilityRequest::Description))
+                    .col(integer(FacilityRequest::RequestedBy))
+                    .col(string(FacilityRequest::Priority).default("normal"))
+                    .col(string(FacilityRequest::Status).default("open"))
+                    .col(integer_null(FacilityRequest::AssignedTo))
+                    .col(date_time(FacilityRequest::CreatedAt))
+                    .col(date_time(FacilityRequest::UpdatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-facility-request-location")
+                            .from(FacilityRequest::Table, FacilityRequest::LocationId)
+                            .to(Location::Table, Location::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-facility-request-requested-by")
+                            .from(FacilityRequest::Table, FacilityRequest::RequestedBy)
+                            .to(Attendee::Table, Attendee::CAPID),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-facility-request-assigned-to")
+                            .from(FacilityRequest::Table, FacilityRequest::AssignedTo)
+                            .to(Attendee::Table, Attendee::CAPID)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(FacilityRequestComment::Table)
+                    .if_not_exists()
+                    .col(pk_auto(FacilityRequestComment::Id))
+                    .col(integer(FacilityRequestComment::RequestId))
+                    .col(integer(FacilityRequestComment::Capid))
+                    .col(text(FacilityRequestComment::Body))
+                    .col(date_time(FacilityRequestComment::CreatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-facility-request-comment-request")
+                            .from(
+                                FacilityRequestComment::Table,
+                                FacilityRequestComment::RequestId,
+                            )
+                            .to(FacilityRequest::Table, FacilityRequest::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-facility-request-comment-capid")
+                            .from(FacilityRequestComment::Table, FacilityRequestComment::Capid)
+                            .to(Attendee::Table, Attendee::CAPID),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(FacilityRequestPhoto::Table)
+                    .if_not_exists()
+                    .col(pk_auto(FacilityRequestPhoto::Id))
+                    .col(integer(FacilityRequestPhoto::RequestId))
+                    .col(string(FacilityRequestPhoto::FileName))
+                    .col(string(FacilityRequestPhoto::ContentType))
+                    .col(date_time(FacilityRequestPhoto::UploadedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-facility-request-photo-request")
+                            .from(FacilityRequestPhoto::Table, FacilityRequestPhoto::RequestId)
+                            .to(FacilityRequest::Table, FacilityRequest::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(FacilityRequestStatus::Table)
+                    .if_not_exists()
+                    .col(pk_auto(FacilityRequestStatus::Id))
+                    .col(integer(FacilityRequestStatus::RequestId))
+                    .col(string_null(FacilityRequestStatus::FromStatus))
+                    .col(string(FacilityRequestStatus::ToStatus))
+                    .col(integer_null(FacilityRequestStatus::ChangedBy))
+                    .col(text_null(FacilityRequestStatus::Note))
+                    .col(date_time(FacilityRequestStatus::ChangedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-facility-request-status-request")
+                            .from(
+                                FacilityRequestStatus::Table,
+                                FacilityRequestStatus::RequestId,
+                            )
+                            .to(FacilityRequest::Table, FacilityRequest::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-facility-request-status-changed-by")
+                            .from(
+                                FacilityRequestStatus::Table,
+                                FacilityRequestStatus::ChangedBy,
+                            )
+                            .to(Attendee::Table, Attendee::CAPID)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(FacilityRequestStatus::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(FacilityRequestPhoto::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(FacilityRequestComment::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(FacilityRequest::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum FacilityRequest {
+    Table,
+    Id,
+    Category,
+    LocationId,
+    Description,
+    RequestedBy,
+    Priority,
+    Status,
+    AssignedTo,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum FacilityRequestComment {
+    Table,
+    Id,
+    RequestId,
+    Capid,
+    Body,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum FacilityRequestPhoto {
+    Table,
+    Id,
+    RequestId,
+    FileName,
+    ContentType,
+    UploadedAt,
+}
+
+#[derive(DeriveIden)]
+enum FacilityRequestStatus {
+    Table,
+    Id,
+    RequestId,
+    FromStatus,
+    ToStatus,
+    ChangedBy,
+    Note,
+    ChangedAt,
+}
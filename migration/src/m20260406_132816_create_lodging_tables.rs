@@ -0,0 +1,146 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Building::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Building::Id))
+                    .col(string_uniq(Building::Name))
+                    .col(text_null(Building::Notes))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Room::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Room::Id))
+                    .col(integer(Room::BuildingId))
+                    .col(string(Room::Name))
+                    .col(string(Room::RoomType))
+                    .col(string(Room::Gender))
+                    .col(integer(Room::Capacity))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-room-building")
+                            .from(Room::Table, Room::BuildingId)
+                            .to(Building::Table, Building::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-room-building-name")
+                    .table(Room::Table)
+                    .col(Room::BuildingId)
+                    .col(Room::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(BedAssignment::Table)
+                    .if_not_exists()
+                    .col(pk_auto(BedAssignment::Id))
+                    .col(integer(BedAssignment::RoomId))
+                    .col(integer(BedAssignment::Bed))
+                    .col(integer_uniq(BedAssignment::Capid))
+                    .col(date_time(BedAssignment::AssignedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-bed-assignment-room")
+                            .from(BedAssignment::Table, BedAssignment::RoomId)
+                            .to(Room::Table, Room::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-bed-assignment-capid")
+                            .from(BedAssignment::Table, BedAssignment::Capid)
+                            .to(Attendee::Table, Attendee::CAPID)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-bed-assignment-room-bed")
+                    .table(BedAssignment::Table)
+                    .col(BedAssignment::RoomId)
+                    .col(BedAssignment::Bed)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(BedAssignment::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().if_exists().table(Room::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().if_exists().table(Building::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Building {
+    Table,
+    Id,
+    Name,
+    Notes,
+}
+
+#[derive(DeriveIden)]
+#[allow(clippy::enum_variant_names)]
+enum Room {
+    Table,
+    Id,
+    BuildingId,
+    Name,
+    RoomType,
+    Gender,
+    Capacity,
+}
+
+#[derive(DeriveIden)]
+enum BedAssignment {
+    Table,
+    Id,
+    RoomId,
+    Bed,
+    Capid,
+    AssignedAt,
+}
@@ -0,0 +1,45 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+use crate::m20260309_143026_create_vehicle_incident_tables::VehicleIncident;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(VehicleIncident::Table)
+                    .add_column(integer_null(VehicleIncident::ClearedBy))
+                    .add_column(date_time_null(VehicleIncident::ClearedAt))
+                    .add_column(text_null(VehicleIncident::Clearance))
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk-vehicle-incident-cleared-by")
+                            .from_tbl(VehicleIncident::Table)
+                            .from_col(VehicleIncident::ClearedBy)
+                            .to_tbl(Attendee::Table)
+                            .to_col(Attendee::CAPID),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(VehicleIncident::Table)
+                    .drop_foreign_key("fk-vehicle-incident-cleared-by")
+                    .drop_column(VehicleIncident::ClearedBy)
+                    .drop_column(VehicleIncident::ClearedAt)
+                    .drop_column(VehicleIncident::Clearance)
+                    .to_owned(),
+            )
+            .await
+    }
+}
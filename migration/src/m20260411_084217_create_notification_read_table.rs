@@ -0,0 +1,108 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260108_142456_create_table::Attendee;
+use crate::m20260326_143318_create_notification_table::Notification;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(NotificationRead::Table)
+                    .if_not_exists()
+                    .col(pk_auto(NotificationRead::Id))
+                    .col(integer(NotificationRead::NotificationId))
+                    .col(integer(NotificationRead::Capid))
+                    .col(date_time(NotificationRead::ReadAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notification-read-notification")
+                            .from(NotificationRead::Table, NotificationRead::NotificationId)
+                            .to(Notification::Table, Notification::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notification-read-capid")
+                            .from(NotificationRead::Table, NotificationRead::Capid)
+                            .to(Attendee::Table, Attendee::CAPID)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-notification-read-recipient")
+                    .table(NotificationRead::Table)
+                    .col(NotificationRead::NotificationId)
+                    .col(NotificationRead::Capid)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Only notifications sent to one person have an unambiguous reader;
+        // flight-wide ones start over as unread for everyone
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "INSERT INTO notification_read (notification_id, capid, read_at) \
+                 SELECT id, capid, read_at FROM notification \
+                 WHERE capid IS NOT NULL AND read_at IS NOT NULL",
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Notification::Table)
+                    .drop_column(Notification::ReadAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Notification::Table)
+                    .add_column(date_time_null(Notification::ReadAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE notification SET read_at = r.read_at FROM notification_read r \
+                 WHERE r.notification_id = notification.id AND r.capid = notification.capid",
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(NotificationRead::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum NotificationRead {
+    Table,
+    Id,
+    NotificationId,
+    Capid,
+    ReadAt,
+}